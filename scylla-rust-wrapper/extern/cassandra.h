@@ -235,6 +235,21 @@ typedef struct CassErrorResult_ CassErrorResult;
  */
 typedef struct CassNode_ CassNode;
 
+/**
+ * Metadata of a host handed out to a custom load balancing callback.
+ *
+ * @struct CassHostMetadata
+ */
+typedef struct CassHostMetadata_ CassHostMetadata;
+
+/**
+ * Routing information of a request handed out to a custom load balancing
+ * callback.
+ *
+ * @struct CassRoutingInfo
+ */
+typedef struct CassRoutingInfo_ CassRoutingInfo;
+
 /**
  * An object used to iterate over a group of rows, columns or collection values.
  *
@@ -629,6 +644,14 @@ typedef enum CassSslVerifyFlags_ {
   CASS_SSL_VERIFY_PEER_IDENTITY_DNS = 0x04
 } CassSslVerifyFlags;
 
+typedef enum CassSslTlsVersion_ {
+  CASS_SSL_TLS_VERSION_DEFAULT = 0x00, /**< Lowest/highest version supported by OpenSSL */
+  CASS_SSL_TLS_VERSION_1_0     = 0x01,
+  CASS_SSL_TLS_VERSION_1_1     = 0x02,
+  CASS_SSL_TLS_VERSION_1_2     = 0x03,
+  CASS_SSL_TLS_VERSION_1_3     = 0x04
+} CassSslTlsVersion;
+
 typedef enum CassProtocolVersion_ {
   CASS_PROTOCOL_VERSION_V1    = 0x01, /**< Deprecated */
   CASS_PROTOCOL_VERSION_V2    = 0x02, /**< Deprecated */
@@ -884,6 +907,14 @@ typedef void (*CassAuthenticatorCleanupCallback)(CassAuthenticator* auth,
  */
 typedef void (*CassAuthenticatorDataCleanupCallback)(void* data);
 
+/**
+ * A callback used to cleanup the data of a callback once the driver
+ * no longer calls it.
+ *
+ * @param[in] data
+ */
+typedef void (*CassDataCleanupCallback)(void* data);
+
 /**
  * Authenticator callbacks
  */
@@ -894,6 +925,71 @@ typedef struct CassAuthenticatorCallbacks_ {
   CassAuthenticatorCleanupCallback cleanup_callback;
 } CassAuthenticatorCallbacks;
 
+/**
+ * Credentials filled in by a credentials callback.
+ *
+ * @struct CassCredentials
+ */
+typedef struct CassCredentials_ CassCredentials;
+
+/**
+ * A callback used to provide plain text credentials. It is called every
+ * time a new connection authenticates, so it may return rotated credentials.
+ *
+ * Use cass_credentials_set() to set the credentials. Not setting them
+ * fails the authentication.
+ *
+ * @param[in] credentials
+ * @param[in] data
+ */
+typedef void (*CassCredentialsCallback)(CassCredentials* credentials,
+                                        void* data);
+
+/**
+ * A callback used to build the query plan of a request. It is called
+ * concurrently for every request (possibly more than once), so it must be
+ * thread-safe.
+ *
+ * The plan is written as indices into the hosts array, most preferred
+ * host first. Indices out of range and repeated ones are ignored.
+ * Returning an empty plan falls back to the load balancing policy
+ * configured by the other settings.
+ *
+ * @param[in] routing_info Only valid for the duration of the callback
+ * @param[in] hosts All hosts known to the driver, only valid for the
+ * duration of the callback
+ * @param[in] hosts_count
+ * @param[out] plan Room for hosts_count indices
+ * @param[in] data
+ * @return The number of indices written to the plan
+ */
+typedef size_t (*CassLoadBalancingCallback)(const CassRoutingInfo* routing_info,
+                                            const CassHostMetadata* const* hosts,
+                                            size_t hosts_count,
+                                            size_t* plan,
+                                            void* data);
+
+/**
+ * A callback used to translate the address of a node discovered by the
+ * driver (e.g. a private address advertised in system.peers) into the
+ * address the driver connects to. It is called concurrently, so it must
+ * be thread-safe.
+ *
+ * @param[in] address The address advertised by the node
+ * @param[in] port
+ * @param[out] translated_address Initialized to the advertised address
+ * @param[out] translated_port Initialized to the advertised port
+ * @param[in] data
+ * @return cass_true if the address was translated, cass_false otherwise.
+ * Connecting to a node whose address cannot be translated fails with
+ * CASS_ERROR_LIB_HOST_RESOLUTION.
+ */
+typedef cass_bool_t (*CassAddressTranslatorCallback)(const CassInet* address,
+                                                     int port,
+                                                     CassInet* translated_address,
+                                                     int* translated_port,
+                                                     void* data);
+
 typedef enum CassHostListenerEvent_ {
   CASS_HOST_LISTENER_EVENT_UP,
   CASS_HOST_LISTENER_EVENT_DOWN,
@@ -1087,6 +1183,72 @@ cass_execution_profile_set_load_balance_dc_aware_n(CassExecProfile* profile,
                                                    unsigned used_hosts_per_remote_dc,
                                                    cass_bool_t allow_remote_dcs_for_local_cl);
 
+/**
+ * Configures the execution profile to use Rack-aware load balancing.
+ * For each query, all live nodes in a primary 'local' rack are tried first,
+ * followed by nodes from the local DC and then nodes from other DCs.
+ *
+ * <b>Note:</b> Profile-based load balancing policy is disabled by default;
+ * cluster load balancing policy is used when profile does not contain a policy.
+ *
+ * @public @memberof CassExecProfile
+ *
+ * @param[in] profile
+ * @param[in] local_dc The primary data center to try first
+ * @param[in] local_rack The primary rack to try first
+ * @return CASS_OK if successful, otherwise an error occurred.
+ *
+ * @see cass_cluster_set_load_balance_rack_aware()
+ */
+CASS_EXPORT CassError
+cass_execution_profile_set_load_balance_rack_aware(CassExecProfile* profile,
+                                                   const char* local_dc,
+                                                   const char* local_rack);
+
+/**
+ * Same as cass_execution_profile_set_load_balance_rack_aware(), but with
+ * lengths for string parameters.
+ *
+ * @public @memberof CassExecProfile
+ *
+ * @param[in] profile
+ * @param[in] local_dc
+ * @param[in] local_dc_length
+ * @param[in] local_rack
+ * @param[in] local_rack_length
+ * @return same as cass_execution_profile_set_load_balance_rack_aware()
+ *
+ * @see cass_execution_profile_set_load_balance_rack_aware()
+ * @see cass_cluster_set_load_balance_rack_aware_n()
+ */
+CASS_EXPORT CassError
+cass_execution_profile_set_load_balance_rack_aware_n(CassExecProfile* profile,
+                                                     const char* local_dc,
+                                                     size_t local_dc_length,
+                                                     const char* local_rack,
+                                                     size_t local_rack_length);
+
+/**
+ * Configures the execution profile to build query plans with a custom
+ * callback.
+ *
+ * @public @memberof CassExecProfile
+ *
+ * @param[in] profile
+ * @param[in] callback
+ * @param[in] cleanup_callback Called when the data is no longer needed,
+ * can be NULL
+ * @param[in] data
+ * @return CASS_OK if successful, otherwise an error occurred
+ *
+ * @see cass_cluster_set_load_balance_custom()
+ */
+CASS_EXPORT CassError
+cass_execution_profile_set_load_balance_custom(CassExecProfile* profile,
+                                               CassLoadBalancingCallback callback,
+                                               CassDataCleanupCallback cleanup_callback,
+                                               void* data);
+
 /**
  * Configures the execution profile to use token-aware request routing or not.
  *
@@ -1483,7 +1645,13 @@ cass_cluster_free(CassCluster* cluster);
  * points. Passing an empty string will clear the contact points. White space
  * is striped from the contact points.
  *
- * Examples: "127.0.0.1" "127.0.0.1,127.0.0.2", "server1.domain.com"
+ * A contact point may specify its own port, otherwise the port set with
+ * cass_cluster_set_port() is used. IPv6 addresses with a port must be
+ * enclosed in brackets. Malformed contact points are rejected with
+ * CASS_ERROR_LIB_BAD_PARAMS and none of the passed contact points is added.
+ *
+ * Examples: "127.0.0.1" "127.0.0.1,127.0.0.2", "server1.domain.com",
+ * "127.0.0.1:9043", "::1", "[::1]:9043"
  *
  * @public @memberof CassCluster
  *
@@ -1618,6 +1786,165 @@ cass_cluster_set_authenticator_callbacks(CassCluster* cluster,
                                          CassAuthenticatorDataCleanupCallback cleanup_callback,
                                          void* data);
 
+/**
+ * Sets a callback providing the plain text credentials. Unlike
+ * cass_cluster_set_credentials(), the credentials are requested anew
+ * every time a connection authenticates, so reconnects pick up
+ * rotated credentials.
+ *
+ * @public @memberof CassCluster
+ *
+ * @param[in] cluster
+ * @param[in] callback
+ * @param[in] cleanup_callback called when the data is no longer needed
+ * @param[in] data
+ * @return CASS_OK if successful, otherwise an error occurred.
+ *
+ * @see cass_credentials_set()
+ */
+CASS_EXPORT CassError
+cass_cluster_set_credentials_callback(CassCluster* cluster,
+                                      CassCredentialsCallback callback,
+                                      CassDataCleanupCallback cleanup_callback,
+                                      void* data);
+
+/**
+ * Reads the plain text credentials from a file every time a connection
+ * authenticates. The first line of the file is the username and the
+ * second one is the password.
+ *
+ * @public @memberof CassCluster
+ *
+ * @param[in] cluster
+ * @param[in] path
+ * @return CASS_OK if successful, otherwise an error occurred.
+ */
+CASS_EXPORT CassError
+cass_cluster_set_credentials_file(CassCluster* cluster,
+                                  const char* path);
+
+/**
+ * Same as cass_cluster_set_credentials_file(), but with lengths for string
+ * parameters.
+ *
+ * @public @memberof CassCluster
+ *
+ * @param[in] cluster
+ * @param[in] path
+ * @param[in] path_length
+ * @return same as cass_cluster_set_credentials_file()
+ *
+ * @see cass_cluster_set_credentials_file()
+ */
+CASS_EXPORT CassError
+cass_cluster_set_credentials_file_n(CassCluster* cluster,
+                                    const char* path,
+                                    size_t path_length);
+
+/**
+ * Reads the plain text credentials from the given environment variables
+ * every time a connection authenticates.
+ *
+ * @public @memberof CassCluster
+ *
+ * @param[in] cluster
+ * @param[in] username_variable
+ * @param[in] password_variable
+ * @return CASS_OK if successful, otherwise an error occurred.
+ */
+CASS_EXPORT CassError
+cass_cluster_set_credentials_env(CassCluster* cluster,
+                                 const char* username_variable,
+                                 const char* password_variable);
+
+/**
+ * Same as cass_cluster_set_credentials_env(), but with lengths for string
+ * parameters.
+ *
+ * @public @memberof CassCluster
+ *
+ * @param[in] cluster
+ * @param[in] username_variable
+ * @param[in] username_variable_length
+ * @param[in] password_variable
+ * @param[in] password_variable_length
+ * @return same as cass_cluster_set_credentials_env()
+ *
+ * @see cass_cluster_set_credentials_env()
+ */
+CASS_EXPORT CassError
+cass_cluster_set_credentials_env_n(CassCluster* cluster,
+                                   const char* username_variable,
+                                   size_t username_variable_length,
+                                   const char* password_variable,
+                                   size_t password_variable_length);
+
+/**
+ * Sets a callback translating the addresses of the nodes discovered by
+ * the driver. Replaces the translations added with
+ * cass_cluster_add_address_translation().
+ *
+ * @public @memberof CassCluster
+ *
+ * @param[in] cluster
+ * @param[in] callback
+ * @param[in] cleanup_callback called when the data is no longer needed
+ * @param[in] data
+ * @return CASS_OK if successful, otherwise an error occurred.
+ */
+CASS_EXPORT CassError
+cass_cluster_set_address_translator_callback(CassCluster* cluster,
+                                             CassAddressTranslatorCallback callback,
+                                             CassDataCleanupCallback cleanup_callback,
+                                             void* data);
+
+/**
+ * Adds a static translation of the address of a node discovered by the
+ * driver. Nodes without a translation cannot be connected to. Replaces
+ * the callback set with cass_cluster_set_address_translator_callback().
+ *
+ * @public @memberof CassCluster
+ *
+ * @param[in] cluster
+ * @param[in] address The IP address advertised by the node
+ * @param[in] port
+ * @param[in] translated_address The IP address to connect to instead
+ * @param[in] translated_port
+ * @return CASS_OK if successful, otherwise an error occurred.
+ */
+CASS_EXPORT CassError
+cass_cluster_add_address_translation(CassCluster* cluster,
+                                     const char* address,
+                                     int port,
+                                     const char* translated_address,
+                                     int translated_port);
+
+/**
+ * Same as cass_cluster_add_address_translation(), but with lengths for
+ * string parameters.
+ *
+ * @public @memberof CassCluster
+ *
+ * @param[in] cluster
+ * @param[in] address
+ * @param[in] address_length
+ * @param[in] port
+ * @param[in] translated_address
+ * @param[in] translated_address_length
+ * @param[in] translated_port
+ * @return same as cass_cluster_add_address_translation()
+ *
+ * @see cass_cluster_add_address_translation()
+ */
+CASS_EXPORT CassError
+cass_cluster_add_address_translation_n(CassCluster* cluster,
+                                       const char* address,
+                                       size_t address_length,
+                                       int port,
+                                       const char* translated_address,
+                                       size_t translated_address_length,
+                                       int translated_port);
+
 /**
  * Sets the protocol version. The driver will automatically downgrade to the lowest
  * supported protocol version.
@@ -1891,24 +2218,21 @@ cass_cluster_set_max_concurrent_creation(CassCluster* cluster,
                                          unsigned num_connections));
 
 /**
- * Sets the threshold for the maximum number of concurrent requests in-flight
- * on a connection before creating a new connection. The number of new connections
- * created will not exceed max_connections_per_host.
+ * Sets the maximum number of requests in-flight on a session. Requests
+ * exceeding this limit fail immediately with
+ * CASS_ERROR_LIB_REQUEST_QUEUE_FULL.
  *
- * <b>Default:</b> 100
+ * <b>Default:</b> No limit
  *
  * @public @memberof CassCluster
  *
- * @deprecated This is no longer useful and does nothing. Expect this to be
- * removed in a future release.
- *
  * @param[in] cluster
  * @param[in] num_requests
  * @return CASS_OK if successful, otherwise an error occurred.
  */
-CASS_EXPORT CASS_DEPRECATED(CassError
+CASS_EXPORT CassError
 cass_cluster_set_max_concurrent_requests_threshold(CassCluster* cluster,
-                                                   unsigned num_requests));
+                                                   unsigned num_requests);
 
 /**
  * Sets the maximum number of requests processed by an IO worker
@@ -1970,46 +2294,38 @@ cass_cluster_set_write_bytes_low_water_mark(CassCluster* cluster,
                                             unsigned num_bytes));
 
 /**
- * Sets the high water mark for the number of requests queued waiting
- * for a connection in a connection pool. Disables writes to a
- * host on an IO worker if the number of requests queued exceed this
- * value.
+ * Sets the high water mark for the number of requests in-flight on
+ * a session. Once the number of requests exceeds this value, new requests
+ * fail immediately with CASS_ERROR_LIB_REQUEST_QUEUE_FULL.
  *
- * <b>Default:</b> 256
+ * <b>Default:</b> No limit
  *
  * @public @memberof CassCluster
  *
- * @deprecated This is no longer useful and does nothing. Expect this to be
- * removed in a future release.
- *
  * @param[in] cluster
  * @param[in] num_requests
  * @return CASS_OK if successful, otherwise an error occurred.
  */
-CASS_EXPORT CASS_DEPRECATED(CassError
+CASS_EXPORT CassError
 cass_cluster_set_pending_requests_high_water_mark(CassCluster* cluster,
-                                                  unsigned num_requests));
+                                                  unsigned num_requests);
 
 /**
- * Sets the low water mark for the number of requests queued waiting
- * for a connection in a connection pool. After exceeding high water mark
- * requests, writes to a host will only resume once the number of requests
- * fall below this value.
+ * Sets the low water mark for the number of requests in-flight on
+ * a session. After exceeding the high water mark, new requests will
+ * only be accepted once the number of requests falls to this value.
  *
- * <b>Default:</b> 128
+ * <b>Default:</b> Half of the high water mark
  *
  * @public @memberof CassCluster
  *
- * @deprecated This is no longer useful and does nothing. Expect this to be
- * removed in a future release.
- *
  * @param[in] cluster
  * @param[in] num_requests
  * @return CASS_OK if successful, otherwise an error occurred.
  */
-CASS_EXPORT CASS_DEPRECATED(CassError
+CASS_EXPORT CassError
 cass_cluster_set_pending_requests_low_water_mark(CassCluster* cluster,
-                                                 unsigned num_requests));
+                                                 unsigned num_requests);
 
 /**
  * Sets the timeout for connecting to a node.
@@ -2219,6 +2535,71 @@ cass_cluster_set_load_balance_dc_aware_n(CassCluster* cluster,
                                          unsigned used_hosts_per_remote_dc,
                                          cass_bool_t allow_remote_dcs_for_local_cl);
 
+/**
+ * Configures the cluster to use Rack-aware load balancing.
+ * For each query, all live nodes in a primary 'local' rack are tried first,
+ * followed by nodes from the local DC and then nodes from other DCs.
+ *
+ * When token-aware routing is enabled, replicas from the local DC are tried
+ * before other nodes, starting with the ones in the local rack.
+ *
+ * @public @memberof CassCluster
+ *
+ * @param[in] cluster
+ * @param[in] local_dc The primary data center to try first
+ * @param[in] local_rack The primary rack to try first
+ * @return CASS_OK if successful, otherwise an error occurred
+ */
+CASS_EXPORT CassError
+cass_cluster_set_load_balance_rack_aware(CassCluster* cluster,
+                                         const char* local_dc,
+                                         const char* local_rack);
+
+/**
+ * Same as cass_cluster_set_load_balance_rack_aware(), but with lengths for
+ * string parameters.
+ *
+ * @public @memberof CassCluster
+ *
+ * @param[in] cluster
+ * @param[in] local_dc
+ * @param[in] local_dc_length
+ * @param[in] local_rack
+ * @param[in] local_rack_length
+ * @return same as cass_cluster_set_load_balance_rack_aware()
+ *
+ * @see cass_cluster_set_load_balance_rack_aware()
+ */
+CASS_EXPORT CassError
+cass_cluster_set_load_balance_rack_aware_n(CassCluster* cluster,
+                                           const char* local_dc,
+                                           size_t local_dc_length,
+                                           const char* local_rack,
+                                           size_t local_rack_length);
+
+/**
+ * Configures the cluster to build query plans with a custom callback.
+ * The load balancing policy configured by the other settings is used
+ * whenever the callback returns no hosts. Host filtering is applied to
+ * the plans built by the callback as well.
+ *
+ * @public @memberof CassCluster
+ *
+ * @param[in] cluster
+ * @param[in] callback
+ * @param[in] cleanup_callback Called when the data is no longer needed,
+ * can be NULL
+ * @param[in] data
+ * @return CASS_OK if successful, otherwise an error occurred
+ *
+ * @see CassLoadBalancingCallback
+ */
+CASS_EXPORT CassError
+cass_cluster_set_load_balance_custom(CassCluster* cluster,
+                                     CassLoadBalancingCallback callback,
+                                     CassDataCleanupCallback cleanup_callback,
+                                     void* data);
+
 /**
  * Configures the cluster to use token-aware request routing or not.
  *
@@ -2490,7 +2871,7 @@ cass_cluster_set_tcp_nodelay(CassCluster* cluster,
  * @param[in] cluster
  * @param[in] enabled
  * @param[in] delay_secs The initial delay in seconds, ignored when
- * `enabled` is false.
+ * `enabled` is false. A delay of 0 is raised to 1 second.
  */
 CASS_EXPORT void
 cass_cluster_set_tcp_keepalive(CassCluster* cluster,
@@ -2611,6 +2992,30 @@ CASS_EXPORT CassError
 cass_cluster_set_use_hostname_resolution(CassCluster* cluster,
                                          cass_bool_t enabled);
 
+/**
+ * Enable/Disable looking up contact points in DNS SRV records. When enabled,
+ * a contact point given as a hostname without a port is resolved to the
+ * targets and ports of its SRV records (e.g. "_cql._tcp.example.com").
+ * A name without SRV records is used as a plain hostname. Targets of the
+ * same priority are ordered by weighted random selection (RFC 2782).
+ *
+ * SRV records are looked up when the session connects. If the session later
+ * loses every known node, the hostnames of the contact points and SRV targets
+ * are resolved again, so that it follows the cluster to new addresses.
+ *
+ * <b>Default:</b> cass_false (disabled).
+ *
+ * @public @memberof CassCluster
+ *
+ * @param[in] cluster
+ * @param[in] enabled
+ *
+ * @see cass_cluster_set_resolve_timeout()
+ */
+CASS_EXPORT void
+cass_cluster_set_use_srv_records(CassCluster* cluster,
+                                 cass_bool_t enabled);
+
 /**
  * Enable/Disable the randomization of the contact points list.
  *
@@ -3155,6 +3560,27 @@ CASS_EXPORT CassFuture*
 cass_session_execute_batch(CassSession* session,
                            const CassBatch* batch);
 
+/**
+ * Replaces the trusted certificates, the client certificate and the private
+ * key used by a connected session with the ones set on the given SSL context.
+ * Connections opened after this call, including reconnects, use the new
+ * material. Existing connections and other sessions are not affected.
+ *
+ * <b>Note:</b> The session must have been connected using a cluster
+ * configured with cass_cluster_set_ssl().
+ *
+ * @public @memberof CassSession
+ *
+ * @param[in] session
+ * @param[in] ssl
+ * @return CASS_OK if successful, otherwise CASS_ERROR_LIB_INVALID_STATE if
+ * the session is not connected, is being connected or closed, or does not
+ * use SSL.
+ */
+CASS_EXPORT CassError
+cass_session_reload_ssl(CassSession* session,
+                        const CassSsl* ssl);
+
 /**
  * Gets a snapshot of this session's schema metadata. The returned
  * snapshot of the schema metadata is not updated. This function
@@ -4608,7 +5034,8 @@ cass_ssl_free(CassSsl* ssl);
  * @public @memberof CassSsl
  *
  * @param[in] ssl
- * @param[in] cert PEM formatted certificate string
+ * @param[in] cert PEM formatted certificate string. It may contain
+ * multiple certificates, all of which are added.
  * @return CASS_OK if successful, otherwise an error occurred
  */
 CASS_EXPORT CassError
@@ -4730,32 +5157,371 @@ cass_ssl_set_private_key_n(CassSsl* ssl,
                            const char* password,
                            size_t password_length);
 
-/***********************************************************************************
+/**
+ * Same as cass_ssl_add_trusted_cert(), but reads the PEM formatted
+ * certificates from a file. All the certificates in the file are added.
  *
- * Authenticator
+ * @public @memberof CassSsl
  *
- ************************************************************************************/
+ * @param[in] ssl
+ * @param[in] path Path to a file with PEM formatted certificates
+ * @return CASS_OK if successful, otherwise an error occurred
+ *
+ * @see cass_ssl_add_trusted_cert()
+ */
+CASS_EXPORT CassError
+cass_ssl_add_trusted_cert_file(CassSsl* ssl,
+                               const char* path);
 
 /**
- * Gets the IP address of the host being authenticated.
+ * Same as cass_ssl_add_trusted_cert_file(), but with lengths for string
+ * parameters.
  *
- * @param[in] auth
- * @param[out] address
+ * @public @memberof CassSsl
  *
- * @public @memberof CassAuthenticator
+ * @param[in] ssl
+ * @param[in] path
+ * @param[in] path_length
+ * @return same as cass_ssl_add_trusted_cert_file()
+ *
+ * @see cass_ssl_add_trusted_cert_file()
  */
-CASS_EXPORT void
+CASS_EXPORT CassError
+cass_ssl_add_trusted_cert_file_n(CassSsl* ssl,
+                                 const char* path,
+                                 size_t path_length);
+
+/**
+ * Adds the certificates from OpenSSL's default CA file and directory
+ * (usually the operating system's trust store) to the trusted certificates.
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @return CASS_OK if successful, otherwise an error occurred
+ */
+CASS_EXPORT CassError
+cass_ssl_add_system_trusted_certs(CassSsl* ssl);
+
+/**
+ * Same as cass_ssl_set_cert(), but reads the PEM formatted certificate
+ * chain from a file.
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @param[in] path Path to a file with the PEM formatted certificate chain
+ * @return CASS_OK if successful, otherwise an error occurred
+ *
+ * @see cass_ssl_set_cert()
+ */
+CASS_EXPORT CassError
+cass_ssl_set_cert_file(CassSsl* ssl,
+                       const char* path);
+
+/**
+ * Same as cass_ssl_set_cert_file(), but with lengths for string
+ * parameters.
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @param[in] path
+ * @param[in] path_length
+ * @return same as cass_ssl_set_cert_file()
+ *
+ * @see cass_ssl_set_cert_file()
+ */
+CASS_EXPORT CassError
+cass_ssl_set_cert_file_n(CassSsl* ssl,
+                         const char* path,
+                         size_t path_length);
+
+/**
+ * Same as cass_ssl_set_private_key(), but reads the PEM formatted
+ * key from a file.
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @param[in] path Path to a file with the PEM formatted key
+ * @param[in] password used to decrypt key
+ * @return CASS_OK if successful, otherwise an error occurred
+ *
+ * @see cass_ssl_set_private_key()
+ */
+CASS_EXPORT CassError
+cass_ssl_set_private_key_file(CassSsl* ssl,
+                              const char* path,
+                              const char* password);
+
+/**
+ * Same as cass_ssl_set_private_key_file(), but with lengths for string
+ * parameters.
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @param[in] path
+ * @param[in] path_length
+ * @param[in] password
+ * @param[in] password_length
+ * @return same as cass_ssl_set_private_key_file()
+ *
+ * @see cass_ssl_set_private_key_file()
+ */
+CASS_EXPORT CassError
+cass_ssl_set_private_key_file_n(CassSsl* ssl,
+                                const char* path,
+                                size_t path_length,
+                                const char* password,
+                                size_t password_length);
+
+/**
+ * Sets the client-side certificate, its chain and the private key
+ * from a DER encoded PKCS#12 bundle. The chain replaces the one set
+ * by a previous call. A bundle which is rejected leaves the
+ * previous settings unchanged.
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @param[in] data DER encoded PKCS#12 bundle
+ * @param[in] data_size
+ * @param[in] password used to decrypt the bundle
+ * @return CASS_OK if successful, otherwise an error occurred
+ */
+CASS_EXPORT CassError
+cass_ssl_set_pkcs12(CassSsl* ssl,
+                    const cass_byte_t* data,
+                    size_t data_size,
+                    const char* password);
+
+/**
+ * Same as cass_ssl_set_pkcs12(), but with lengths for string
+ * parameters.
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @param[in] data
+ * @param[in] data_size
+ * @param[in] password
+ * @param[in] password_length
+ * @return same as cass_ssl_set_pkcs12()
+ *
+ * @see cass_ssl_set_pkcs12()
+ */
+CASS_EXPORT CassError
+cass_ssl_set_pkcs12_n(CassSsl* ssl,
+                      const cass_byte_t* data,
+                      size_t data_size,
+                      const char* password,
+                      size_t password_length);
+
+/**
+ * Same as cass_ssl_set_pkcs12(), but reads the bundle from a file
+ * (e.g. a .p12 or .pfx file).
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @param[in] path Path to the PKCS#12 bundle
+ * @param[in] password used to decrypt the bundle
+ * @return CASS_OK if successful, otherwise an error occurred
+ *
+ * @see cass_ssl_set_pkcs12()
+ */
+CASS_EXPORT CassError
+cass_ssl_set_pkcs12_file(CassSsl* ssl,
+                         const char* path,
+                         const char* password);
+
+/**
+ * Same as cass_ssl_set_pkcs12_file(), but with lengths for string
+ * parameters.
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @param[in] path
+ * @param[in] path_length
+ * @param[in] password
+ * @param[in] password_length
+ * @return same as cass_ssl_set_pkcs12_file()
+ *
+ * @see cass_ssl_set_pkcs12_file()
+ */
+CASS_EXPORT CassError
+cass_ssl_set_pkcs12_file_n(CassSsl* ssl,
+                           const char* path,
+                           size_t path_length,
+                           const char* password,
+                           size_t password_length);
+
+/**
+ * Sets the minimum TLS protocol version used for connections.
+ *
+ * <b>Default:</b> CASS_SSL_TLS_VERSION_DEFAULT
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @param[in] version
+ * @return CASS_OK if successful, otherwise CASS_ERROR_SSL_PROTOCOL_ERROR
+ * if the version is invalid or not supported by OpenSSL.
+ */
+CASS_EXPORT CassError
+cass_ssl_set_min_protocol_version(CassSsl* ssl,
+                                  CassSslTlsVersion version);
+
+/**
+ * Sets the maximum TLS protocol version used for connections.
+ *
+ * <b>Default:</b> CASS_SSL_TLS_VERSION_DEFAULT
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @param[in] version
+ * @return CASS_OK if successful, otherwise CASS_ERROR_SSL_PROTOCOL_ERROR
+ * if the version is invalid or not supported by OpenSSL.
+ */
+CASS_EXPORT CassError
+cass_ssl_set_max_protocol_version(CassSsl* ssl,
+                                  CassSslTlsVersion version);
+
+/**
+ * Sets the ciphers allowed for TLS 1.2 and older connections, in the
+ * OpenSSL cipher list format (e.g. "ECDHE-RSA-AES256-GCM-SHA384:ECDHE-RSA-AES128-GCM-SHA256").
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @param[in] cipher_list
+ * @return CASS_OK if successful, otherwise CASS_ERROR_SSL_PROTOCOL_ERROR
+ * if none of the ciphers could be selected.
+ */
+CASS_EXPORT CassError
+cass_ssl_set_cipher_list(CassSsl* ssl,
+                         const char* cipher_list);
+
+/**
+ * Same as cass_ssl_set_cipher_list(), but with lengths for string
+ * parameters.
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @param[in] cipher_list
+ * @param[in] cipher_list_length
+ * @return same as cass_ssl_set_cipher_list()
+ *
+ * @see cass_ssl_set_cipher_list()
+ */
+CASS_EXPORT CassError
+cass_ssl_set_cipher_list_n(CassSsl* ssl,
+                           const char* cipher_list,
+                           size_t cipher_list_length);
+
+/**
+ * Sets the ciphersuites allowed for TLS 1.3 connections, as a colon
+ * separated list (e.g. "TLS_AES_256_GCM_SHA384:TLS_AES_128_GCM_SHA256").
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @param[in] ciphersuites
+ * @return CASS_OK if successful, otherwise CASS_ERROR_SSL_PROTOCOL_ERROR
+ * if the list is invalid.
+ */
+CASS_EXPORT CassError
+cass_ssl_set_ciphersuites(CassSsl* ssl,
+                          const char* ciphersuites);
+
+/**
+ * Same as cass_ssl_set_ciphersuites(), but with lengths for string
+ * parameters.
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @param[in] ciphersuites
+ * @param[in] ciphersuites_length
+ * @return same as cass_ssl_set_ciphersuites()
+ *
+ * @see cass_ssl_set_ciphersuites()
+ */
+CASS_EXPORT CassError
+cass_ssl_set_ciphersuites_n(CassSsl* ssl,
+                            const char* ciphersuites,
+                            size_t ciphersuites_length);
+
+/**
+ * Sets the key exchange groups allowed for connections, as a colon
+ * separated list (e.g. "X25519:P-256").
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @param[in] groups
+ * @return CASS_OK if successful, otherwise CASS_ERROR_SSL_PROTOCOL_ERROR
+ * if the list is invalid.
+ */
+CASS_EXPORT CassError
+cass_ssl_set_groups(CassSsl* ssl,
+                    const char* groups);
+
+/**
+ * Same as cass_ssl_set_groups(), but with lengths for string
+ * parameters.
+ *
+ * @public @memberof CassSsl
+ *
+ * @param[in] ssl
+ * @param[in] groups
+ * @param[in] groups_length
+ * @return same as cass_ssl_set_groups()
+ *
+ * @see cass_ssl_set_groups()
+ */
+CASS_EXPORT CassError
+cass_ssl_set_groups_n(CassSsl* ssl,
+                      const char* groups,
+                      size_t groups_length);
+
+/***********************************************************************************
+ *
+ * Authenticator
+ *
+ ************************************************************************************/
+
+/**
+ * Gets the IP address of the host being authenticated.
+ *
+ * <b>Note:</b> The host is not known to the authenticator, so the address
+ * is set with an address_length of 0, which is not a valid address.
+ *
+ * @param[in] auth
+ * @param[out] address
+ *
+ * @public @memberof CassAuthenticator
+ */
+CASS_EXPORT void
 cass_authenticator_address(const CassAuthenticator* auth,
                            CassInet* address);
 
 /**
  * Gets the hostname of the host being authenticated.
  *
+ * <b>Note:</b> The host is not known to the authenticator, so NULL is
+ * returned and the length is set to 0.
+ *
  * @public @memberof CassAuthenticator
  *
  * @param[in] auth
  * @param[out] length
- * @return A null-terminated string.
+ * @return A null-terminated string, or NULL if the hostname is unknown.
  */
 CASS_EXPORT const char*
 cass_authenticator_hostname(const CassAuthenticator* auth,
@@ -4859,6 +5625,156 @@ cass_authenticator_set_error_n(CassAuthenticator* auth,
                                const char* message,
                                size_t message_length);
 
+/**
+ * Sets the credentials provided by a credentials callback.
+ *
+ * @public @memberof CassCredentials
+ *
+ * @param[in] credentials
+ * @param[in] username
+ * @param[in] password
+ *
+ * @see cass_cluster_set_credentials_callback()
+ */
+CASS_EXPORT void
+cass_credentials_set(CassCredentials* credentials,
+                     const char* username,
+                     const char* password);
+
+/**
+ * Same as cass_credentials_set(), but with lengths for string
+ * parameters.
+ *
+ * @public @memberof CassCredentials
+ *
+ * @param[in] credentials
+ * @param[in] username
+ * @param[in] username_length
+ * @param[in] password
+ * @param[in] password_length
+ *
+ * @see cass_credentials_set()
+ */
+CASS_EXPORT void
+cass_credentials_set_n(CassCredentials* credentials,
+                       const char* username,
+                       size_t username_length,
+                       const char* password,
+                       size_t password_length);
+
+/***********************************************************************************
+ *
+ * Custom load balancing
+ *
+ ***********************************************************************************/
+
+/**
+ * Gets the address of a host.
+ *
+ * @public @memberof CassHostMetadata
+ *
+ * @param[in] host
+ * @param[out] address
+ */
+CASS_EXPORT void
+cass_host_metadata_address(const CassHostMetadata* host,
+                           CassInet* address);
+
+/**
+ * Gets the port of a host.
+ *
+ * @public @memberof CassHostMetadata
+ *
+ * @param[in] host
+ * @return The port
+ */
+CASS_EXPORT cass_uint16_t
+cass_host_metadata_port(const CassHostMetadata* host);
+
+/**
+ * Gets the datacenter of a host.
+ *
+ * @public @memberof CassHostMetadata
+ *
+ * @param[in] host
+ * @param[out] datacenter
+ * @param[out] datacenter_length
+ * @return CASS_OK if successful, CASS_ERROR_LIB_NULL_VALUE if the
+ * datacenter is unknown.
+ */
+CASS_EXPORT CassError
+cass_host_metadata_datacenter(const CassHostMetadata* host,
+                              const char** datacenter,
+                              size_t* datacenter_length);
+
+/**
+ * Gets the rack of a host.
+ *
+ * @public @memberof CassHostMetadata
+ *
+ * @param[in] host
+ * @param[out] rack
+ * @param[out] rack_length
+ * @return CASS_OK if successful, CASS_ERROR_LIB_NULL_VALUE if the
+ * rack is unknown.
+ */
+CASS_EXPORT CassError
+cass_host_metadata_rack(const CassHostMetadata* host,
+                        const char** rack,
+                        size_t* rack_length);
+
+/**
+ * Checks whether a host is considered up by the driver.
+ *
+ * @public @memberof CassHostMetadata
+ *
+ * @param[in] host
+ * @return cass_true if the host is up, otherwise cass_false.
+ */
+CASS_EXPORT cass_bool_t
+cass_host_metadata_is_up(const CassHostMetadata* host);
+
+/**
+ * Gets the keyspace of a request.
+ *
+ * @public @memberof CassRoutingInfo
+ *
+ * @param[in] routing_info
+ * @param[out] keyspace
+ * @param[out] keyspace_length
+ * @return CASS_OK if successful, CASS_ERROR_LIB_NULL_VALUE if the
+ * keyspace is unknown.
+ */
+CASS_EXPORT CassError
+cass_routing_info_keyspace(const CassRoutingInfo* routing_info,
+                           const char** keyspace,
+                           size_t* keyspace_length);
+
+/**
+ * Gets the token of the partition key of a request.
+ *
+ * @public @memberof CassRoutingInfo
+ *
+ * @param[in] routing_info
+ * @param[out] token
+ * @return CASS_OK if successful, CASS_ERROR_LIB_NULL_VALUE if the
+ * token is unknown.
+ */
+CASS_EXPORT CassError
+cass_routing_info_token(const CassRoutingInfo* routing_info,
+                        cass_int64_t* token);
+
+/**
+ * Gets the consistency of a request.
+ *
+ * @public @memberof CassRoutingInfo
+ *
+ * @param[in] routing_info
+ * @return The consistency
+ */
+CASS_EXPORT CassConsistency
+cass_routing_info_consistency(const CassRoutingInfo* routing_info);
+
 /***********************************************************************************
  *
  * Future
@@ -0,0 +1,193 @@
+use crate::inet::CassInet;
+use crate::types::*;
+use async_trait::async_trait;
+use scylla::transport::errors::TranslationError;
+use scylla::transport::session::AddressTranslator;
+use scylla::transport::topology::UntranslatedPeer;
+use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+use std::net::{IpAddr, SocketAddr};
+use std::os::raw::{c_int, c_void};
+
+type AddressTranslatorCallback = unsafe extern "C" fn(
+    address: *const CassInet,
+    port: c_int,
+    translated_address: *mut CassInet,
+    translated_port: *mut c_int,
+    data: *mut c_void,
+) -> cass_bool_t;
+
+pub type CassAddressTranslatorCallback = Option<AddressTranslatorCallback>;
+
+pub(crate) enum AddressTranslation {
+    Callback {
+        callback: AddressTranslatorCallback,
+        data_cleanup_callback: CassDataCleanupCallback,
+        data: *mut c_void,
+    },
+    Map(HashMap<SocketAddr, SocketAddr>),
+}
+
+// Rewrites the addresses of the nodes discovered by the driver, so that the driver
+// can reach nodes advertising addresses that are private to their network.
+pub(crate) struct CassAddressTranslator {
+    translation: AddressTranslation,
+}
+
+// The data of the callback is only ever handed back to the user, who is
+// responsible for making the callback thread-safe.
+unsafe impl Send for CassAddressTranslator {}
+unsafe impl Sync for CassAddressTranslator {}
+
+impl CassAddressTranslator {
+    pub(crate) fn new(translation: AddressTranslation) -> Self {
+        Self { translation }
+    }
+
+    pub(crate) fn translation(&self) -> &AddressTranslation {
+        &self.translation
+    }
+
+    fn translate(&self, address: SocketAddr) -> Option<SocketAddr> {
+        match &self.translation {
+            AddressTranslation::Callback { callback, data, .. } => {
+                let untranslated_inet: CassInet = address.ip().into();
+                let mut translated_inet = untranslated_inet;
+                let mut translated_port = address.port() as c_int;
+                let translated = unsafe {
+                    callback(
+                        &untranslated_inet,
+                        address.port() as c_int,
+                        &mut translated_inet,
+                        &mut translated_port,
+                        *data,
+                    )
+                };
+                if translated == cass_false {
+                    return None;
+                }
+
+                let ip: IpAddr = translated_inet.try_into().ok()?;
+                let port = u16::try_from(translated_port).ok()?;
+                Some(SocketAddr::new(ip, port))
+            }
+            AddressTranslation::Map(map) => map.get(&address).copied(),
+        }
+    }
+}
+
+impl Drop for AddressTranslation {
+    fn drop(&mut self) {
+        if let AddressTranslation::Callback {
+            data_cleanup_callback: Some(cleanup),
+            data,
+            ..
+        } = *self
+        {
+            unsafe { cleanup(data) }
+        }
+    }
+}
+
+#[async_trait]
+impl AddressTranslator for CassAddressTranslator {
+    async fn translate_address(
+        &self,
+        untranslated_peer: &UntranslatedPeer,
+    ) -> Result<SocketAddr, TranslationError> {
+        let address = untranslated_peer.untranslated_address;
+        self.translate(address).ok_or_else(|| {
+            tracing::error!("Unable to translate the address of node {}", address);
+            TranslationError::NoRuleForAddress
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    unsafe extern "C" fn translate_to_localhost(
+        address: *const CassInet,
+        port: c_int,
+        translated_address: *mut CassInet,
+        translated_port: *mut c_int,
+        _data: *mut c_void,
+    ) -> cass_bool_t {
+        // Only addresses from the 10.0.0.0/8 network are translated
+        let ip: IpAddr = (*address).try_into().unwrap();
+        match ip {
+            IpAddr::V4(v4) if v4.octets()[0] == 10 => {
+                *translated_address = "127.0.0.1".parse::<IpAddr>().unwrap().into();
+                *translated_port = port + v4.octets()[3] as c_int;
+                cass_true
+            }
+            _ => cass_false,
+        }
+    }
+
+    unsafe extern "C" fn invalid_port(
+        _address: *const CassInet,
+        _port: c_int,
+        _translated_address: *mut CassInet,
+        translated_port: *mut c_int,
+        _data: *mut c_void,
+    ) -> cass_bool_t {
+        *translated_port = -1;
+        cass_true
+    }
+
+    unsafe extern "C" fn count_cleanups(data: *mut c_void) {
+        (*(data as *const AtomicUsize)).fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn addr(s: &str) -> SocketAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn callback_translation() {
+        let cleanups = AtomicUsize::new(0);
+        {
+            let translator = CassAddressTranslator::new(AddressTranslation::Callback {
+                callback: translate_to_localhost,
+                data_cleanup_callback: Some(count_cleanups),
+                data: &cleanups as *const AtomicUsize as *mut c_void,
+            });
+            assert_eq!(
+                translator.translate(addr("10.0.0.2:9042")),
+                Some(addr("127.0.0.1:9044"))
+            );
+            assert_eq!(translator.translate(addr("192.168.0.2:9042")), None);
+            assert_eq!(cleanups.load(Ordering::Relaxed), 0);
+        }
+        assert_eq!(cleanups.load(Ordering::Relaxed), 1);
+
+        let translator = CassAddressTranslator::new(AddressTranslation::Callback {
+            callback: invalid_port,
+            data_cleanup_callback: None,
+            data: std::ptr::null_mut(),
+        });
+        assert_eq!(translator.translate(addr("10.0.0.2:9042")), None);
+    }
+
+    #[test]
+    fn map_translation() {
+        let translator = CassAddressTranslator::new(AddressTranslation::Map(HashMap::from([
+            (addr("10.0.0.2:9042"), addr("203.0.113.2:19042")),
+            (addr("10.0.0.3:9042"), addr("203.0.113.3:19042")),
+        ])));
+
+        assert_eq!(
+            translator.translate(addr("10.0.0.2:9042")),
+            Some(addr("203.0.113.2:19042"))
+        );
+        assert_eq!(
+            translator.translate(addr("10.0.0.3:9042")),
+            Some(addr("203.0.113.3:19042"))
+        );
+        // Addresses without a translation are not reachable
+        assert_eq!(translator.translate(addr("10.0.0.4:9042")), None);
+    }
+}
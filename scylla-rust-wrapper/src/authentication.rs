@@ -0,0 +1,701 @@
+use crate::argconv::*;
+use crate::inet::CassInet;
+use crate::types::{size_t, CassDataCleanupCallback};
+use async_trait::async_trait;
+use scylla::authentication::{
+    AuthError, AuthenticatorProvider, AuthenticatorSession, PlainTextAuthenticator,
+};
+use std::os::raw::{c_char, c_void};
+use std::sync::Arc;
+
+pub type CassAuthenticatorInitialCallback =
+    Option<unsafe extern "C" fn(auth: *mut CassAuthenticator, data: *mut c_void)>;
+
+pub type CassAuthenticatorChallengeCallback = Option<
+    unsafe extern "C" fn(
+        auth: *mut CassAuthenticator,
+        data: *mut c_void,
+        token: *const c_char,
+        token_size: size_t,
+    ),
+>;
+
+pub type CassAuthenticatorSuccessCallback = Option<
+    unsafe extern "C" fn(
+        auth: *mut CassAuthenticator,
+        data: *mut c_void,
+        token: *const c_char,
+        token_size: size_t,
+    ),
+>;
+
+pub type CassAuthenticatorCleanupCallback =
+    Option<unsafe extern "C" fn(auth: *mut CassAuthenticator, data: *mut c_void)>;
+
+pub type CassAuthenticatorDataCleanupCallback = Option<unsafe extern "C" fn(data: *mut c_void)>;
+
+pub type CassCredentialsCallback =
+    Option<unsafe extern "C" fn(credentials: *mut CassCredentials, data: *mut c_void)>;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CassAuthenticatorCallbacks {
+    pub initial_callback: CassAuthenticatorInitialCallback,
+    pub challenge_callback: CassAuthenticatorChallengeCallback,
+    pub success_callback: CassAuthenticatorSuccessCallback,
+    pub cleanup_callback: CassAuthenticatorCleanupCallback,
+}
+
+// State of a single authentication exchange, handed out to the user callbacks.
+pub struct CassAuthenticator {
+    class_name: String,
+    exchange_data: *mut c_void,
+    response: Option<Vec<u8>>,
+    error: Option<String>,
+}
+
+impl CassAuthenticator {
+    fn new(class_name: &str) -> Self {
+        // Null-terminated, as `cass_authenticator_class_name` returns a C string.
+        let mut class_name = class_name.to_owned();
+        class_name.push('\0');
+
+        Self {
+            class_name,
+            exchange_data: std::ptr::null_mut(),
+            response: None,
+            error: None,
+        }
+    }
+
+    fn take_result(&mut self) -> Result<Option<Vec<u8>>, AuthError> {
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => Ok(self.response.take()),
+        }
+    }
+}
+
+// Authenticator provider backed by the callbacks registered with
+// `cass_cluster_set_authenticator_callbacks`. A new exchange (and thus a new
+// `CassAuthenticator`) is started for every connection the driver opens.
+pub(crate) struct CassAuthenticatorProvider {
+    callbacks: CassAuthenticatorCallbacks,
+    data_cleanup_callback: CassAuthenticatorDataCleanupCallback,
+    data: *mut c_void,
+}
+
+// The user data is only ever accessed by the user-provided callbacks, which are
+// required by the CPP driver's API to be thread-safe.
+unsafe impl Send for CassAuthenticatorProvider {}
+unsafe impl Sync for CassAuthenticatorProvider {}
+
+impl CassAuthenticatorProvider {
+    pub(crate) fn new(
+        callbacks: CassAuthenticatorCallbacks,
+        data_cleanup_callback: CassAuthenticatorDataCleanupCallback,
+        data: *mut c_void,
+    ) -> Self {
+        Self {
+            callbacks,
+            data_cleanup_callback,
+            data,
+        }
+    }
+}
+
+impl Drop for CassAuthenticatorProvider {
+    fn drop(&mut self) {
+        if let Some(cleanup) = self.data_cleanup_callback {
+            unsafe { cleanup(self.data) }
+        }
+    }
+}
+
+#[async_trait]
+impl AuthenticatorProvider for CassAuthenticatorProvider {
+    async fn start_authentication_session(
+        &self,
+        authenticator_name: &str,
+    ) -> Result<(Option<Vec<u8>>, Box<dyn AuthenticatorSession>), AuthError> {
+        let mut session = CassAuthenticatorSession {
+            callbacks: self.callbacks,
+            data: self.data,
+            // Boxed, so that the pointer handed to the callbacks stays valid
+            // when the session is moved around.
+            authenticator: Box::new(CassAuthenticator::new(authenticator_name)),
+        };
+
+        if let Some(initial) = self.callbacks.initial_callback {
+            unsafe { initial(session.authenticator.as_mut(), self.data) };
+        }
+
+        // If the initial callback failed, the session is dropped here,
+        // which invokes the cleanup callback.
+        let response = session.authenticator.take_result()?;
+
+        Ok((response, Box::new(session)))
+    }
+}
+
+struct CassAuthenticatorSession {
+    callbacks: CassAuthenticatorCallbacks,
+    data: *mut c_void,
+    authenticator: Box<CassAuthenticator>,
+}
+
+// See the comment on `CassAuthenticatorProvider`.
+unsafe impl Send for CassAuthenticatorSession {}
+unsafe impl Sync for CassAuthenticatorSession {}
+
+impl CassAuthenticatorSession {
+    fn token_to_c(token: Option<&[u8]>) -> (*const c_char, size_t) {
+        match token {
+            Some(token) => (token.as_ptr() as *const c_char, token.len() as size_t),
+            None => (std::ptr::null(), 0),
+        }
+    }
+}
+
+#[async_trait]
+impl AuthenticatorSession for CassAuthenticatorSession {
+    async fn evaluate_challenge(
+        &mut self,
+        token: Option<&[u8]>,
+    ) -> Result<Option<Vec<u8>>, AuthError> {
+        if let Some(challenge) = self.callbacks.challenge_callback {
+            let (token, token_size) = Self::token_to_c(token);
+            unsafe { challenge(self.authenticator.as_mut(), self.data, token, token_size) };
+        }
+
+        self.authenticator.take_result()
+    }
+
+    async fn success(&mut self, token: Option<&[u8]>) -> Result<(), AuthError> {
+        if let Some(success) = self.callbacks.success_callback {
+            let (token, token_size) = Self::token_to_c(token);
+            unsafe { success(self.authenticator.as_mut(), self.data, token, token_size) };
+        }
+
+        self.authenticator.take_result().map(|_| ())
+    }
+}
+
+impl Drop for CassAuthenticatorSession {
+    fn drop(&mut self) {
+        if let Some(cleanup) = self.callbacks.cleanup_callback {
+            unsafe { cleanup(self.authenticator.as_mut(), self.data) }
+        }
+    }
+}
+
+pub(crate) fn new_authenticator_provider(
+    callbacks: CassAuthenticatorCallbacks,
+    data_cleanup_callback: CassAuthenticatorDataCleanupCallback,
+    data: *mut c_void,
+) -> Arc<dyn AuthenticatorProvider> {
+    Arc::new(CassAuthenticatorProvider::new(
+        callbacks,
+        data_cleanup_callback,
+        data,
+    ))
+}
+
+// Credentials handed out to `CassCredentialsCallback`, to be filled in by the user.
+#[derive(Default)]
+pub struct CassCredentials {
+    username: Option<String>,
+    password: Option<String>,
+}
+
+pub(crate) enum CredentialsSource {
+    Callback {
+        callback: unsafe extern "C" fn(credentials: *mut CassCredentials, data: *mut c_void),
+        data_cleanup_callback: CassDataCleanupCallback,
+        data: *mut c_void,
+    },
+    // File with the username in the first line and the password in the second one.
+    File(String),
+    Env {
+        username_variable: String,
+        password_variable: String,
+    },
+}
+
+// Plain text authenticator provider which fetches the credentials anew for every
+// connection the driver opens, so that rotated credentials are picked up by reconnects.
+pub(crate) struct CassCredentialsProvider {
+    source: CredentialsSource,
+}
+
+// See the comment on `CassAuthenticatorProvider`.
+unsafe impl Send for CassCredentialsProvider {}
+unsafe impl Sync for CassCredentialsProvider {}
+
+impl CassCredentialsProvider {
+    async fn credentials(&self) -> Result<(String, String), AuthError> {
+        match &self.source {
+            CredentialsSource::Callback { callback, data, .. } => {
+                let mut credentials = CassCredentials::default();
+                unsafe { callback(&mut credentials, *data) };
+
+                match credentials {
+                    CassCredentials {
+                        username: Some(username),
+                        password: Some(password),
+                    } => Ok((username, password)),
+                    _ => Err("Credentials callback did not provide credentials".to_string()),
+                }
+            }
+            CredentialsSource::File(path) => {
+                let contents = tokio::fs::read_to_string(path)
+                    .await
+                    .map_err(|err| format!("Unable to read credentials from {}: {}", path, err))?;
+                let mut lines = contents.lines();
+
+                match (lines.next(), lines.next()) {
+                    (Some(username), Some(password)) => {
+                        Ok((username.to_string(), password.to_string()))
+                    }
+                    _ => Err(format!(
+                        "Credentials file {} must contain a username and a password line",
+                        path
+                    )),
+                }
+            }
+            CredentialsSource::Env {
+                username_variable,
+                password_variable,
+            } => {
+                let read_variable = |name: &str| {
+                    std::env::var(name).map_err(|err| {
+                        format!("Unable to read credentials from ${}: {}", name, err)
+                    })
+                };
+
+                Ok((
+                    read_variable(username_variable)?,
+                    read_variable(password_variable)?,
+                ))
+            }
+        }
+    }
+}
+
+impl Drop for CassCredentialsProvider {
+    fn drop(&mut self) {
+        if let CredentialsSource::Callback {
+            data_cleanup_callback: Some(cleanup),
+            data,
+            ..
+        } = self.source
+        {
+            unsafe { cleanup(data) }
+        }
+    }
+}
+
+#[async_trait]
+impl AuthenticatorProvider for CassCredentialsProvider {
+    async fn start_authentication_session(
+        &self,
+        authenticator_name: &str,
+    ) -> Result<(Option<Vec<u8>>, Box<dyn AuthenticatorSession>), AuthError> {
+        let (username, password) = self.credentials().await.map_err(|err| {
+            tracing::error!("{}", err);
+            err
+        })?;
+
+        PlainTextAuthenticator::new(username, password)
+            .start_authentication_session(authenticator_name)
+            .await
+    }
+}
+
+pub(crate) fn new_credentials_provider(
+    source: CredentialsSource,
+) -> Arc<dyn AuthenticatorProvider> {
+    Arc::new(CassCredentialsProvider { source })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_credentials_set(
+    credentials: *mut CassCredentials,
+    username: *const c_char,
+    password: *const c_char,
+) {
+    cass_credentials_set_n(
+        credentials,
+        username,
+        strlen(username),
+        password,
+        strlen(password),
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_credentials_set_n(
+    credentials: *mut CassCredentials,
+    username: *const c_char,
+    username_length: size_t,
+    password: *const c_char,
+    password_length: size_t,
+) {
+    let credentials = ptr_to_ref_mut(credentials);
+    credentials.username = ptr_to_cstr_n(username, username_length).map(ToOwned::to_owned);
+    credentials.password = ptr_to_cstr_n(password, password_length).map(ToOwned::to_owned);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_authenticator_address(
+    _auth: *const CassAuthenticator,
+    address: *mut CassInet,
+) {
+    // The Rust driver does not expose the address of the node being authenticated
+    // to the authenticator provider, so no address is reported: a zero length
+    // does not make a valid IPv4 or IPv6 address.
+    std::ptr::write(
+        address,
+        CassInet {
+            address: [0; 16],
+            address_length: 0,
+        },
+    );
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_authenticator_hostname(
+    _auth: *const CassAuthenticator,
+    length: *mut size_t,
+) -> *const c_char {
+    // Same as above, the hostname of the node is unknown.
+    if !length.is_null() {
+        *length = 0;
+    }
+    std::ptr::null()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_authenticator_class_name(
+    auth: *const CassAuthenticator,
+    length: *mut size_t,
+) -> *const c_char {
+    let auth = ptr_to_ref(auth);
+    if !length.is_null() {
+        // Do not count the null terminator.
+        *length = (auth.class_name.len() - 1) as size_t;
+    }
+    auth.class_name.as_ptr() as *const c_char
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_authenticator_exchange_data(
+    auth: *mut CassAuthenticator,
+) -> *mut c_void {
+    ptr_to_ref(auth).exchange_data
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_authenticator_set_exchange_data(
+    auth: *mut CassAuthenticator,
+    exchange_data: *mut c_void,
+) {
+    ptr_to_ref_mut(auth).exchange_data = exchange_data;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_authenticator_response(
+    auth: *mut CassAuthenticator,
+    size: size_t,
+) -> *mut c_char {
+    let auth = ptr_to_ref_mut(auth);
+    let response = auth.response.insert(vec![0; size as usize]);
+    response.as_mut_ptr() as *mut c_char
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_authenticator_set_response(
+    auth: *mut CassAuthenticator,
+    response: *const c_char,
+    response_size: size_t,
+) {
+    let auth = ptr_to_ref_mut(auth);
+    auth.response = if response.is_null() {
+        None
+    } else {
+        Some(std::slice::from_raw_parts(response as *const u8, response_size as usize).to_vec())
+    };
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_authenticator_set_error(
+    auth: *mut CassAuthenticator,
+    message: *const c_char,
+) {
+    cass_authenticator_set_error_n(auth, message, strlen(message))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_authenticator_set_error_n(
+    auth: *mut CassAuthenticator,
+    message: *const c_char,
+    message_length: size_t,
+) {
+    let auth = ptr_to_ref_mut(auth);
+    let message = if message.is_null() {
+        ""
+    } else {
+        ptr_to_cstr_n(message, message_length).unwrap_or("")
+    };
+    auth.error = Some(message.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RUNTIME;
+    use rusty_fork::rusty_fork_test;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct CallCounters {
+        initial: AtomicUsize,
+        challenge: AtomicUsize,
+        success: AtomicUsize,
+        cleanup: AtomicUsize,
+        data_cleanup: AtomicUsize,
+        credentials: AtomicUsize,
+    }
+
+    unsafe extern "C" fn initial(auth: *mut CassAuthenticator, data: *mut c_void) {
+        let counters = &*(data as *const CallCounters);
+        counters.initial.fetch_add(1, Ordering::SeqCst);
+
+        let mut length = 0;
+        let class_name = cass_authenticator_class_name(auth, &mut length);
+        assert_eq!(
+            ptr_to_cstr_n(class_name, length),
+            Some("CustomAuthenticator")
+        );
+
+        // The node being authenticated is unknown.
+        let mut address: CassInet = "127.0.0.1".parse::<std::net::IpAddr>().unwrap().into();
+        cass_authenticator_address(auth, &mut address);
+        assert_eq!(address.address_length, 0);
+        let mut length = 1;
+        assert!(cass_authenticator_hostname(auth, &mut length).is_null());
+        assert_eq!(length, 0);
+
+        let response = b"initial";
+        cass_authenticator_set_response(
+            auth,
+            response.as_ptr() as *const c_char,
+            response.len() as size_t,
+        );
+    }
+
+    unsafe extern "C" fn challenge(
+        auth: *mut CassAuthenticator,
+        data: *mut c_void,
+        token: *const c_char,
+        token_size: size_t,
+    ) {
+        let counters = &*(data as *const CallCounters);
+        counters.challenge.fetch_add(1, Ordering::SeqCst);
+
+        if ptr_to_cstr_n(token, token_size) == Some("fail") {
+            cass_authenticator_set_error(auth, "Challenge failed\0".as_ptr() as *const c_char);
+            return;
+        }
+
+        let response = cass_authenticator_response(auth, 2);
+        std::ptr::copy_nonoverlapping("ok".as_ptr() as *const c_char, response, 2);
+    }
+
+    unsafe extern "C" fn success(
+        _auth: *mut CassAuthenticator,
+        data: *mut c_void,
+        _token: *const c_char,
+        _token_size: size_t,
+    ) {
+        let counters = &*(data as *const CallCounters);
+        counters.success.fetch_add(1, Ordering::SeqCst);
+    }
+
+    unsafe extern "C" fn cleanup(_auth: *mut CassAuthenticator, data: *mut c_void) {
+        let counters = &*(data as *const CallCounters);
+        counters.cleanup.fetch_add(1, Ordering::SeqCst);
+    }
+
+    unsafe extern "C" fn data_cleanup(data: *mut c_void) {
+        let counters = &*(data as *const CallCounters);
+        counters.data_cleanup.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(100)]
+    async fn authenticator_callbacks_are_invoked() {
+        let counters = CallCounters::default();
+        let callbacks = CassAuthenticatorCallbacks {
+            initial_callback: Some(initial),
+            challenge_callback: Some(challenge),
+            success_callback: Some(success),
+            cleanup_callback: Some(cleanup),
+        };
+        let provider = new_authenticator_provider(
+            callbacks,
+            Some(data_cleanup),
+            &counters as *const CallCounters as *mut c_void,
+        );
+
+        {
+            let (response, mut session) = provider
+                .start_authentication_session("CustomAuthenticator")
+                .await
+                .unwrap();
+            assert_eq!(response.as_deref(), Some(&b"initial"[..]));
+
+            let response = session.evaluate_challenge(Some(b"token")).await.unwrap();
+            assert_eq!(response.as_deref(), Some(&b"ok"[..]));
+
+            session.success(None).await.unwrap();
+        }
+        assert_eq!(counters.initial.load(Ordering::SeqCst), 1);
+        assert_eq!(counters.challenge.load(Ordering::SeqCst), 1);
+        assert_eq!(counters.success.load(Ordering::SeqCst), 1);
+        assert_eq!(counters.cleanup.load(Ordering::SeqCst), 1);
+
+        {
+            let (_, mut session) = provider
+                .start_authentication_session("CustomAuthenticator")
+                .await
+                .unwrap();
+            let error = session.evaluate_challenge(Some(b"fail")).await.unwrap_err();
+            assert_eq!(error, "Challenge failed");
+        }
+        assert_eq!(counters.initial.load(Ordering::SeqCst), 2);
+        assert_eq!(counters.challenge.load(Ordering::SeqCst), 2);
+        assert_eq!(counters.cleanup.load(Ordering::SeqCst), 2);
+
+        assert_eq!(counters.data_cleanup.load(Ordering::SeqCst), 0);
+        drop(provider);
+        assert_eq!(counters.data_cleanup.load(Ordering::SeqCst), 1);
+    }
+
+    unsafe extern "C" fn credentials_callback(
+        credentials: *mut CassCredentials,
+        data: *mut c_void,
+    ) {
+        let counters = &*(data as *const CallCounters);
+        // Every call provides a new password, as if it was rotated.
+        let call = counters.credentials.fetch_add(1, Ordering::SeqCst);
+        let password = format!("password{}", call);
+        cass_credentials_set_n(
+            credentials,
+            "user".as_ptr() as *const c_char,
+            4,
+            password.as_ptr() as *const c_char,
+            password.len() as size_t,
+        );
+    }
+
+    unsafe extern "C" fn empty_credentials_callback(
+        _credentials: *mut CassCredentials,
+        _data: *mut c_void,
+    ) {
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(100)]
+    async fn credentials_are_fetched_for_every_connection() {
+        let counters = CallCounters::default();
+        let provider = new_credentials_provider(CredentialsSource::Callback {
+            callback: credentials_callback,
+            data_cleanup_callback: Some(data_cleanup),
+            data: &counters as *const CallCounters as *mut c_void,
+        });
+
+        for call in 0..2 {
+            let (response, _) = provider
+                .start_authentication_session("PasswordAuthenticator")
+                .await
+                .unwrap();
+            assert_eq!(
+                response.unwrap(),
+                format!("\0user\0password{}", call).into_bytes()
+            );
+        }
+        assert_eq!(counters.credentials.load(Ordering::SeqCst), 2);
+        assert_eq!(counters.initial.load(Ordering::SeqCst), 0);
+
+        drop(provider);
+        assert_eq!(counters.data_cleanup.load(Ordering::SeqCst), 1);
+
+        let provider = new_credentials_provider(CredentialsSource::Callback {
+            callback: empty_credentials_callback,
+            data_cleanup_callback: None,
+            data: std::ptr::null_mut(),
+        });
+        assert!(provider
+            .start_authentication_session("PasswordAuthenticator")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(100)]
+    async fn credentials_from_file() {
+        let path = std::env::temp_dir().join(format!("cass_credentials_{}", std::process::id()));
+        let provider =
+            new_credentials_provider(CredentialsSource::File(path.to_str().unwrap().to_string()));
+
+        // Missing file
+        assert!(provider
+            .start_authentication_session("PasswordAuthenticator")
+            .await
+            .is_err());
+
+        std::fs::write(&path, "user\npassword1\n").unwrap();
+        let (response, _) = provider
+            .start_authentication_session("PasswordAuthenticator")
+            .await
+            .unwrap();
+        assert_eq!(response.unwrap(), b"\0user\0password1");
+
+        // Rotated password
+        std::fs::write(&path, "user\npassword2\n").unwrap();
+        let (response, _) = provider
+            .start_authentication_session("PasswordAuthenticator")
+            .await
+            .unwrap();
+        assert_eq!(response.unwrap(), b"\0user\0password2");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    rusty_fork_test! {
+        #![rusty_fork(timeout_ms = 1000)]
+        #[test]
+        fn credentials_from_env() {
+            // Runs in a forked process, so that modifying the environment
+            // does not race with other tests.
+            let provider = new_credentials_provider(CredentialsSource::Env {
+                username_variable: "CASS_TEST_USERNAME".to_string(),
+                password_variable: "CASS_TEST_PASSWORD".to_string(),
+            });
+            RUNTIME.block_on(async {
+                assert!(provider
+                    .start_authentication_session("PasswordAuthenticator")
+                    .await
+                    .is_err());
+
+                std::env::set_var("CASS_TEST_USERNAME", "user");
+                std::env::set_var("CASS_TEST_PASSWORD", "password");
+                let (response, _) = provider
+                    .start_authentication_session("PasswordAuthenticator")
+                    .await
+                    .unwrap();
+                assert_eq!(response.unwrap(), b"\0user\0password");
+            });
+        }
+    }
+}
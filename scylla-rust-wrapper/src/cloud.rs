@@ -0,0 +1,392 @@
+use crate::cass_error::CassError;
+use openssl::pkey::PKey;
+use openssl::ssl::{Ssl, SslContext, SslContextBuilder, SslMethod, SslVerifyMode};
+use openssl::x509::X509;
+use serde_json::Value;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{Read, Seek, Write};
+use std::net::{IpAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use zip::ZipArchive;
+
+const CONFIG_FILE: &str = "config.json";
+const CA_FILE: &str = "ca.crt";
+const CERT_FILE: &str = "cert";
+const KEY_FILE: &str = "key";
+
+const METADATA_PATH: &str = "/metadata";
+
+// Connection settings extracted from a cloud secure connection bundle.
+pub(crate) struct CloudConfig {
+    // Endpoint of the metadata service, which tells where the SNI proxy
+    // fronting the cloud cluster is and which nodes are behind it.
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) username: Option<String>,
+    pub(crate) password: Option<String>,
+    pub(crate) local_dc: Option<String>,
+    // Used for the connections to the cluster, which go through the SNI proxy.
+    pub(crate) ssl_context: SslContext,
+    metadata_ssl_context: SslContext,
+}
+
+// Contact information returned by the metadata service.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct CloudMetadata {
+    pub(crate) sni_proxy_address: String,
+    pub(crate) host_ids: Vec<String>,
+    pub(crate) local_dc: Option<String>,
+}
+
+impl CloudConfig {
+    pub(crate) fn from_bundle(path: &str) -> Result<Self, CassError> {
+        let file = File::open(path).map_err(|err| {
+            tracing::error!(
+                "Unable to open cloud secure connection bundle {}: {}",
+                path,
+                err
+            );
+            CassError::CASS_ERROR_LIB_BAD_PARAMS
+        })?;
+
+        Self::from_reader(file)
+    }
+
+    pub(crate) fn from_reader(reader: impl Read + Seek) -> Result<Self, CassError> {
+        let mut bundle = ZipArchive::new(reader).map_err(|err| {
+            tracing::error!("Unable to unzip cloud secure connection bundle: {}", err);
+            CassError::CASS_ERROR_LIB_BAD_PARAMS
+        })?;
+
+        let config: Value = serde_json::from_slice(&read_bundle_file(&mut bundle, CONFIG_FILE)?)
+            .map_err(|err| {
+                tracing::error!("Unable to parse {}: {}", CONFIG_FILE, err);
+                CassError::CASS_ERROR_LIB_BAD_PARAMS
+            })?;
+
+        let host = config
+            .get("host")
+            .and_then(Value::as_str)
+            .ok_or_else(|| missing_field("host"))?
+            .to_owned();
+        let port = config
+            .get("port")
+            .and_then(Value::as_u64)
+            .and_then(|port| u16::try_from(port).ok())
+            .ok_or_else(|| missing_field("port"))?;
+        let get_optional_string = |name: &str| {
+            config
+                .get(name)
+                .and_then(Value::as_str)
+                .map(ToOwned::to_owned)
+        };
+
+        let ca = read_bundle_file(&mut bundle, CA_FILE)?;
+        let cert = read_bundle_file(&mut bundle, CERT_FILE)?;
+        let key = read_bundle_file(&mut bundle, KEY_FILE)?;
+
+        Ok(Self {
+            host,
+            port,
+            username: get_optional_string("username"),
+            password: get_optional_string("password"),
+            local_dc: get_optional_string("localDC"),
+            ssl_context: ssl_context_builder(&ca, &cert, &key)?.build(),
+            metadata_ssl_context: ssl_context_builder(&ca, &cert, &key)?.build(),
+        })
+    }
+
+    // Queries the metadata service. Blocks the calling thread for up to `timeout`
+    // per network operation.
+    pub(crate) fn fetch_metadata(&self, timeout: Duration) -> Result<CloudMetadata, String> {
+        let endpoint = format!("{}:{}", self.host, self.port);
+        let address = endpoint
+            .to_socket_addrs()
+            .map_err(|err| format!("Unable to resolve {}: {}", endpoint, err))?
+            .next()
+            .ok_or_else(|| format!("Unable to resolve {}", endpoint))?;
+        let stream = TcpStream::connect_timeout(&address, timeout)
+            .map_err(|err| format!("Unable to connect to {}: {}", endpoint, err))?;
+        stream
+            .set_read_timeout(Some(timeout))
+            .and_then(|_| stream.set_write_timeout(Some(timeout)))
+            .map_err(|err| err.to_string())?;
+
+        let mut ssl = Ssl::new(&self.metadata_ssl_context).map_err(|err| err.to_string())?;
+        if self.host.parse::<IpAddr>().is_err() {
+            ssl.set_hostname(&self.host)
+                .map_err(|err| err.to_string())?;
+        }
+        let mut stream = ssl
+            .connect(stream)
+            .map_err(|err| format!("TLS handshake with {} failed: {}", endpoint, err))?;
+
+        // HTTP/1.0, so that the response is neither chunked nor kept alive.
+        write!(
+            stream,
+            "GET {} HTTP/1.0\r\nHost: {}\r\n\r\n",
+            METADATA_PATH, endpoint
+        )
+        .map_err(|err| format!("Unable to query {}: {}", endpoint, err))?;
+        let mut response = Vec::new();
+        // Servers often close the connection without a TLS close_notify.
+        if let Err(err) = stream.read_to_end(&mut response) {
+            if response.is_empty() {
+                return Err(format!("Unable to query {}: {}", endpoint, err));
+            }
+        }
+
+        parse_metadata_response(&response)
+    }
+}
+
+fn parse_metadata_response(response: &[u8]) -> Result<CloudMetadata, String> {
+    let response = std::str::from_utf8(response)
+        .map_err(|_| "Metadata service response is not valid UTF-8".to_string())?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| "Malformed metadata service response".to_string())?;
+    let status_line = head.lines().next().unwrap_or_default();
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        return Err(format!("Metadata service responded with {}", status_line));
+    }
+
+    let metadata: Value = serde_json::from_str(body)
+        .map_err(|err| format!("Unable to parse metadata service response: {}", err))?;
+    let contact_info = metadata
+        .get("contact_info")
+        .ok_or_else(|| "Missing contact_info in metadata service response".to_string())?;
+    let sni_proxy_address = contact_info
+        .get("sni_proxy_address")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "Missing sni_proxy_address in metadata service response".to_string())?
+        .to_owned();
+    let host_ids = contact_info
+        .get("contact_points")
+        .and_then(Value::as_array)
+        .map(|contact_points| {
+            contact_points
+                .iter()
+                .filter_map(Value::as_str)
+                .map(ToOwned::to_owned)
+                .collect::<Vec<_>>()
+        })
+        .filter(|host_ids| !host_ids.is_empty())
+        .ok_or_else(|| "Missing contact_points in metadata service response".to_string())?;
+
+    Ok(CloudMetadata {
+        sni_proxy_address,
+        host_ids,
+        local_dc: contact_info
+            .get("local_dc")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned),
+    })
+}
+
+fn missing_field(name: &str) -> CassError {
+    tracing::error!("Missing or invalid {} in {}", name, CONFIG_FILE);
+    CassError::CASS_ERROR_LIB_BAD_PARAMS
+}
+
+fn read_bundle_file(
+    bundle: &mut ZipArchive<impl Read + Seek>,
+    name: &str,
+) -> Result<Vec<u8>, CassError> {
+    let mut contents = Vec::new();
+    bundle
+        .by_name(name)
+        .map_err(|err| err.to_string())
+        .and_then(|mut file| {
+            file.read_to_end(&mut contents)
+                .map_err(|err| err.to_string())
+        })
+        .map_err(|err| {
+            tracing::error!(
+                "Unable to read {} from cloud secure connection bundle: {}",
+                name,
+                err
+            );
+            CassError::CASS_ERROR_LIB_BAD_PARAMS
+        })?;
+
+    Ok(contents)
+}
+
+fn ssl_context_builder(ca: &[u8], cert: &[u8], key: &[u8]) -> Result<SslContextBuilder, CassError> {
+    let ca = X509::from_pem(ca).map_err(|_| CassError::CASS_ERROR_SSL_INVALID_CERT)?;
+    let cert = X509::from_pem(cert).map_err(|_| CassError::CASS_ERROR_SSL_INVALID_CERT)?;
+    let key = PKey::private_key_from_pem(key)
+        .map_err(|_| CassError::CASS_ERROR_SSL_INVALID_PRIVATE_KEY)?;
+
+    let mut builder: SslContextBuilder = SslContext::builder(SslMethod::tls())
+        .map_err(|_| CassError::CASS_ERROR_LIB_INTERNAL_ERROR)?;
+    builder
+        .cert_store_mut()
+        .add_cert(ca)
+        .map_err(|_| CassError::CASS_ERROR_SSL_INVALID_CERT)?;
+    builder
+        .set_certificate(&cert)
+        .map_err(|_| CassError::CASS_ERROR_SSL_INVALID_CERT)?;
+    builder
+        .set_private_key(&key)
+        .map_err(|_| CassError::CASS_ERROR_SSL_INVALID_PRIVATE_KEY)?;
+    builder.set_verify(SslVerifyMode::PEER);
+
+    Ok(builder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::self_signed_cert_and_key;
+    use openssl::ssl::NameType;
+    use std::io::Cursor;
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    const METADATA_RESPONSE: &str = "HTTP/1.0 200 OK\r\nContent-Type: application/json\r\n\r\n\
+        {\"version\": 1, \"contact_info\": {\"type\": \"sni_proxy\", \"local_dc\": \"dc1\", \
+        \"contact_points\": [\"host1\", \"host2\"], \"sni_proxy_address\": \"localhost:29042\"}}";
+
+    // Accepts the given number of TLS connections, answering each request with the response.
+    // Returns the server names sent by the clients.
+    fn serve(
+        listener: TcpListener,
+        cert: &[u8],
+        key: &[u8],
+        connections: usize,
+        response: &'static str,
+    ) -> JoinHandle<Vec<Option<String>>> {
+        let mut builder = SslContext::builder(SslMethod::tls()).unwrap();
+        builder
+            .set_certificate(&X509::from_pem(cert).unwrap())
+            .unwrap();
+        builder
+            .set_private_key(&PKey::private_key_from_pem(key).unwrap())
+            .unwrap();
+        let context = builder.build();
+
+        std::thread::spawn(move || {
+            (0..connections)
+                .map(|_| {
+                    let (stream, _) = listener.accept().unwrap();
+                    let mut stream = Ssl::new(&context).unwrap().accept(stream).unwrap();
+                    let server_name = stream
+                        .ssl()
+                        .servername(NameType::HOST_NAME)
+                        .map(ToOwned::to_owned);
+                    let mut request = [0; 1024];
+                    let _ = stream.read(&mut request);
+                    stream.write_all(response.as_bytes()).unwrap();
+                    let _ = stream.shutdown();
+                    server_name
+                })
+                .collect()
+        })
+    }
+
+    fn make_bundle(files: &[(&str, &[u8])]) -> Cursor<Vec<u8>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in files {
+            writer.start_file(*name, FileOptions::default()).unwrap();
+            writer.write_all(contents).unwrap();
+        }
+        let mut bundle = writer.finish().unwrap();
+        bundle.set_position(0);
+        bundle
+    }
+
+    #[test]
+    fn cloud_config_from_bundle() {
+        let (cert, key) = self_signed_cert_and_key();
+
+        {
+            // Valid bundle
+            let config =
+                br#"{"host": "127.0.0.1", "port": 29042, "username": "user", "password": "pass"}"#;
+            let bundle = make_bundle(&[
+                (CONFIG_FILE, &config[..]),
+                (CA_FILE, &cert[..]),
+                (CERT_FILE, &cert[..]),
+                (KEY_FILE, &key[..]),
+            ]);
+            let config = CloudConfig::from_reader(bundle).unwrap();
+            assert_eq!(config.host, "127.0.0.1");
+            assert_eq!(config.port, 29042);
+            assert_eq!(config.username.as_deref(), Some("user"));
+            assert_eq!(config.password.as_deref(), Some("pass"));
+            assert_eq!(config.local_dc, None);
+        }
+        {
+            // Missing port
+            let bundle = make_bundle(&[
+                (CONFIG_FILE, &br#"{"host": "127.0.0.1"}"#[..]),
+                (CA_FILE, &cert[..]),
+                (CERT_FILE, &cert[..]),
+                (KEY_FILE, &key[..]),
+            ]);
+            assert!(CloudConfig::from_reader(bundle).is_err());
+        }
+        {
+            // Missing key
+            let bundle = make_bundle(&[
+                (CONFIG_FILE, &br#"{"host": "127.0.0.1", "port": 29042}"#[..]),
+                (CA_FILE, &cert[..]),
+                (CERT_FILE, &cert[..]),
+            ]);
+            assert!(CloudConfig::from_reader(bundle).is_err());
+        }
+        {
+            // Not a zip file at all
+            assert!(CloudConfig::from_reader(Cursor::new(b"not a zip".to_vec())).is_err());
+            assert!(CloudConfig::from_bundle("invalid_filename").is_err());
+        }
+    }
+
+    #[test]
+    fn metadata_response() {
+        assert_eq!(
+            parse_metadata_response(METADATA_RESPONSE.as_bytes()),
+            Ok(CloudMetadata {
+                sni_proxy_address: "localhost:29042".to_string(),
+                host_ids: vec!["host1".to_string(), "host2".to_string()],
+                local_dc: Some("dc1".to_string()),
+            })
+        );
+
+        assert!(parse_metadata_response(b"HTTP/1.0 404 Not Found\r\n\r\n").is_err());
+        assert!(parse_metadata_response(b"HTTP/1.0 200 OK\r\n\r\n{}").is_err());
+        assert!(parse_metadata_response(
+            b"HTTP/1.0 200 OK\r\n\r\n{\"contact_info\": {\"sni_proxy_address\": \"localhost:29042\"}}"
+        )
+        .is_err());
+        assert!(parse_metadata_response(b"garbage").is_err());
+    }
+
+    #[test]
+    fn metadata_service() {
+        let (cert, key) = self_signed_cert_and_key();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let config = format!(r#"{{"host": "127.0.0.1", "port": {}}}"#, address.port());
+        let bundle = make_bundle(&[
+            (CONFIG_FILE, config.as_bytes()),
+            (CA_FILE, &cert[..]),
+            (CERT_FILE, &cert[..]),
+            (KEY_FILE, &key[..]),
+        ]);
+        let config = CloudConfig::from_reader(bundle).unwrap();
+        let server = serve(listener, &cert, &key, 1, METADATA_RESPONSE);
+
+        let metadata = config.fetch_metadata(Duration::from_secs(5)).unwrap();
+        assert_eq!(metadata.sni_proxy_address, "localhost:29042");
+        assert_eq!(metadata.host_ids, vec!["host1", "host2"]);
+
+        // The metadata service is not sent a server name when its host is an IP address.
+        assert_eq!(server.join().unwrap(), vec![None]);
+    }
+}
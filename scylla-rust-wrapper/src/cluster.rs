@@ -1,15 +1,34 @@
+use crate::address_translation::{
+    AddressTranslation, CassAddressTranslator, CassAddressTranslatorCallback,
+};
 use crate::argconv::*;
+use crate::authentication::{
+    new_authenticator_provider, new_credentials_provider, CassAuthenticatorCallbacks,
+    CassAuthenticatorDataCleanupCallback, CassCredentialsCallback, CredentialsSource,
+};
 use crate::cass_error::CassError;
 use crate::cass_types::CassConsistency;
+use crate::cloud::CloudConfig;
 use crate::exec_profile::{exec_profile_builder_modify, CassExecProfile, ExecProfileName};
 use crate::future::CassFuture;
+use crate::load_balancing::{
+    CassHostFilter, CassLoadBalancingCallback, CustomPolicy, CustomPolicyCallback, FilteringConfig,
+    FilteringPolicy, RackAwarePolicy, RemoteDcPolicy,
+};
+use crate::request_throttler::RequestThrottlingConfig;
+use crate::resolver::{resolve_host, resolve_srv, ResolveConfig};
 use crate::retry_policy::CassRetryPolicy;
 use crate::retry_policy::RetryPolicy::*;
-use crate::ssl::CassSsl;
+use crate::ssl::{CassSsl, PeerIdentityVerifier};
+use crate::timestamp_generator::{
+    CassTimestampGen, MonotonicTimestampGenerator, TimestampGenerator,
+};
+use crate::tls_tunnel::{TlsTunnels, TunnelAddressTranslator};
 use crate::types::*;
 use core::time::Duration;
-use openssl::ssl::SslContextBuilder;
+use openssl::ssl::{SslContext, SslContextBuilder};
 use openssl_sys::SSL_CTX_up_ref;
+use scylla::authentication::AuthenticatorProvider;
 use scylla::execution_profile::ExecutionProfileBuilder;
 use scylla::frame::Compression;
 use scylla::load_balancing::LatencyAwarenessBuilder;
@@ -17,11 +36,14 @@ use scylla::load_balancing::{DefaultPolicyBuilder, LoadBalancingPolicy};
 use scylla::retry_policy::RetryPolicy;
 use scylla::speculative_execution::SimpleSpeculativeExecutionPolicy;
 use scylla::statement::{Consistency, SerialConsistency};
+use scylla::transport::session::{AddressTranslator, PoolSize};
 use scylla::SessionBuilder;
 use std::collections::HashMap;
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 use std::future::Future;
-use std::os::raw::{c_char, c_int, c_uint};
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::num::NonZeroUsize;
+use std::os::raw::{c_char, c_int, c_uint, c_void};
 use std::sync::Arc;
 
 include!(concat!(env!("OUT_DIR"), "/cppdriver_compression_types.rs"));
@@ -29,33 +51,79 @@ include!(concat!(env!("OUT_DIR"), "/cppdriver_compression_types.rs"));
 #[derive(Clone, Debug)]
 pub(crate) struct LoadBalancingConfig {
     pub(crate) token_awareness_enabled: bool,
+    pub(crate) token_aware_shuffling_enabled: bool,
     pub(crate) dc_awareness: Option<DcAwareness>,
+    pub(crate) rack_awareness: Option<RackAwareness>,
     pub(crate) latency_awareness_enabled: bool,
     pub(crate) latency_awareness_builder: LatencyAwarenessBuilder,
+    pub(crate) filtering: FilteringConfig,
+    pub(crate) custom_policy: Option<Arc<CustomPolicyCallback>>,
 }
 impl LoadBalancingConfig {
     // This is `async` to prevent running this function from beyond tokio context,
     // as it results in panic due to DefaultPolicyBuilder::build() spawning a tokio task.
     pub(crate) async fn build(self) -> Arc<dyn LoadBalancingPolicy> {
-        let mut builder = DefaultPolicyBuilder::new().token_aware(self.token_awareness_enabled);
+        let mut builder = DefaultPolicyBuilder::new()
+            .token_aware(self.token_awareness_enabled)
+            .enable_shuffling_replicas(self.token_aware_shuffling_enabled)
+            .permit_dc_failover(self.permits_dc_failover());
         if let Some(dc_awareness) = self.dc_awareness.as_ref() {
-            builder = builder
-                .prefer_datacenter(dc_awareness.local_dc.clone())
-                .permit_dc_failover(true)
+            builder = builder.prefer_datacenter(dc_awareness.local_dc.clone())
+        }
+        if let Some(rack_awareness) = self.rack_awareness.as_ref() {
+            builder = builder.prefer_datacenter(rack_awareness.local_dc.clone())
         }
         if self.latency_awareness_enabled {
             builder = builder.latency_awareness(self.latency_awareness_builder);
         }
-        builder.build()
+        let mut policy = builder.build();
+
+        // Without remote hosts the default policy never leaves the local datacenter,
+        // otherwise the remote part of its plans has to be restricted.
+        if let Some(dc_awareness) = self
+            .dc_awareness
+            .filter(|dc| dc.used_hosts_per_remote_dc > 0)
+        {
+            policy = Arc::new(RemoteDcPolicy::new(policy, dc_awareness));
+        }
+        if let Some(rack_awareness) = self.rack_awareness {
+            policy = Arc::new(RackAwarePolicy::new(
+                policy,
+                rack_awareness,
+                self.token_awareness_enabled,
+            ));
+        }
+        if let Some(callback) = self.custom_policy {
+            policy = Arc::new(CustomPolicy::new(policy, callback));
+        }
+
+        if self.filtering.is_enabled() {
+            Arc::new(FilteringPolicy::new(policy, self.filtering))
+        } else {
+            policy
+        }
+    }
+
+    // Remote datacenters are only used if DC awareness permits some of their hosts.
+    // Without DC awareness the failover stays permitted, e.g. for rack awareness
+    // on its own, like the CPP driver does.
+    fn permits_dc_failover(&self) -> bool {
+        self.dc_awareness.as_ref().map_or(true, |dc_awareness| {
+            dc_awareness.used_hosts_per_remote_dc > 0
+        })
     }
 }
 impl Default for LoadBalancingConfig {
     fn default() -> Self {
         Self {
             token_awareness_enabled: true,
+            token_aware_shuffling_enabled: true,
             dc_awareness: None,
+            rack_awareness: None,
             latency_awareness_enabled: false,
             latency_awareness_builder: Default::default(),
+            filtering: Default::default(),
+            custom_policy: None,
         }
     }
 }
@@ -63,6 +131,88 @@ impl Default for LoadBalancingConfig {
 #[derive(Clone, Debug)]
 pub(crate) struct DcAwareness {
     pub(crate) local_dc: String,
+    pub(crate) used_hosts_per_remote_dc: u32,
+    pub(crate) allow_remote_dcs_for_local_cl: bool,
+}
+
+impl DcAwareness {
+    pub(crate) fn new(local_dc: String) -> Self {
+        Self {
+            local_dc,
+            used_hosts_per_remote_dc: 0,
+            allow_remote_dcs_for_local_cl: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct RackAwareness {
+    pub(crate) local_dc: String,
+    pub(crate) local_rack: String,
+}
+
+// A contact point without a port of its own uses the port of the cluster.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ContactPoint {
+    host: String,
+    port: Option<u16>,
+}
+
+impl ContactPoint {
+    fn new(host: String) -> Self {
+        Self { host, port: None }
+    }
+
+    // Accepts hostnames and IP literals, optionally followed by a port.
+    // IPv6 literals with a port must be enclosed in brackets, e.g. `[::1]:9042`.
+    fn parse(contact_point: &str) -> Option<Self> {
+        if let Ok(ip) = contact_point.parse::<IpAddr>() {
+            return Some(Self::new(ip.to_string()));
+        }
+        if let Some(bracketed) = contact_point.strip_prefix('[') {
+            let (ip, port) = match bracketed.split_once(']')? {
+                (ip, "") => (ip, None),
+                (ip, port) => (ip, Some(parse_port(port.strip_prefix(':')?)?)),
+            };
+            return Some(Self {
+                host: ip.parse::<Ipv6Addr>().ok()?.to_string(),
+                port,
+            });
+        }
+
+        let (host, port) = match contact_point.split_once(':') {
+            Some((host, port)) => (host, Some(parse_port(port)?)),
+            None => (contact_point, None),
+        };
+        let is_valid_hostname = !host.is_empty()
+            && host
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_'));
+        is_valid_hostname.then(|| Self {
+            host: host.to_string(),
+            port,
+        })
+    }
+
+    // Hostnames without a port may name SRV records.
+    fn srv_name(&self) -> Option<&str> {
+        match (self.host.parse::<IpAddr>(), self.port) {
+            (Err(_), None) => Some(&self.host),
+            _ => None,
+        }
+    }
+
+    fn known_node(&self, default_port: u16) -> String {
+        let port = self.port.unwrap_or(default_port);
+        match self.host.parse::<IpAddr>() {
+            Ok(IpAddr::V6(ip)) => format!("[{}]:{}", ip, port),
+            _ => format!("{}:{}", self.host, port),
+        }
+    }
+}
+
+fn parse_port(port: &str) -> Option<u16> {
+    port.parse().ok().filter(|port| *port != 0)
 }
 
 #[derive(Clone)]
@@ -71,46 +221,294 @@ pub struct CassCluster {
     default_execution_profile_builder: ExecutionProfileBuilder,
     execution_profile_map: HashMap<ExecProfileName, CassExecProfile>,
 
-    contact_points: Vec<String>,
+    contact_points: Vec<ContactPoint>,
     port: u16,
+    resolve_config: ResolveConfig,
 
     load_balancing_config: LoadBalancingConfig,
 
     use_beta_protocol_version: bool,
     auth_username: Option<String>,
     auth_password: Option<String>,
+    authenticator_provider: Option<Arc<dyn AuthenticatorProvider>>,
+    peer_identity_verifier: Option<Arc<PeerIdentityVerifier>>,
+    ssl: Option<Arc<CassSsl>>,
+    address_translator: Option<Arc<CassAddressTranslator>>,
+    cloud_config: Option<Arc<CloudConfig>>,
+
+    // None keeps the default pool size of the Rust driver, i.e. one connection per shard.
+    pool_size: Option<PoolSize>,
+
+    request_throttling_config: RequestThrottlingConfig,
+
+    timestamp_generator: Arc<CassTimestampGen>,
 }
 
 impl CassCluster {
     pub(crate) fn execution_profile_map(&self) -> &HashMap<ExecProfileName, CassExecProfile> {
         &self.execution_profile_map
     }
+
+    // A new context for every session, see `CassSsl::session_context()`.
+    pub(crate) fn session_ssl_context(&self) -> Option<SslContext> {
+        self.ssl
+            .as_ref()
+            .map(|ssl| unsafe { ssl.session_context() })
+    }
+
+    pub(crate) fn request_throttling_config(&self) -> &RequestThrottlingConfig {
+        &self.request_throttling_config
+    }
+
+    pub(crate) fn timestamp_generator(&self) -> &Arc<CassTimestampGen> {
+        &self.timestamp_generator
+    }
 }
 
 pub struct CassCustomPayload;
 
 // We want to make sure that the returned future does not depend
 // on the provided &CassCluster, hence the `static here.
-pub fn build_session_builder(
+pub(crate) fn build_session_builder(
     cluster: &CassCluster,
-) -> impl Future<Output = SessionBuilder> + 'static {
-    let known_nodes = cluster
-        .contact_points
-        .iter()
-        .map(|cp| format!("{}:{}", cp, cluster.port));
+    ssl_context: Option<SslContext>,
+) -> impl Future<Output = Result<SessionBuilder, (CassError, String)>> + 'static {
+    let contact_points = cluster.contact_points.clone();
+    let port = cluster.port;
+    let resolve_config = cluster.resolve_config.clone();
+    let cloud_config = cluster.cloud_config.clone();
     let mut execution_profile_builder = cluster.default_execution_profile_builder.clone();
-    let load_balancing_config = cluster.load_balancing_config.clone();
-    let mut session_builder = cluster.session_builder.clone().known_nodes(known_nodes);
+    let mut load_balancing_config = cluster.load_balancing_config.clone();
+    let mut session_builder = cluster.session_builder.clone();
     if let (Some(username), Some(password)) = (&cluster.auth_username, &cluster.auth_password) {
         session_builder = session_builder.user(username, password)
     }
+    if let Some(authenticator_provider) = &cluster.authenticator_provider {
+        session_builder = session_builder.authenticator_provider(authenticator_provider.clone())
+    }
+    if let Some(pool_size) = cluster.pool_size {
+        session_builder = session_builder.pool_size(pool_size);
+    }
+    if let Some(ssl_context) = ssl_context {
+        session_builder.config.ssl_context = Some(ssl_context);
+    }
+    let address_translator = cluster
+        .address_translator
+        .clone()
+        .map(|address_translator| address_translator as Arc<dyn AddressTranslator>);
+    let peer_identity_verifier = cluster
+        .peer_identity_verifier
+        .clone()
+        .filter(|verifier| verifier.is_enabled());
+    let profile_filterings = std::iter::once(&cluster.load_balancing_config)
+        .chain(
+            cluster
+                .execution_profile_map
+                .values()
+                .map(CassExecProfile::load_balancing_config),
+        )
+        .map(|config| config.filtering.clone())
+        .collect();
+    let host_filter = CassHostFilter::new(profile_filterings);
+    if host_filter.is_needed() {
+        session_builder = session_builder.host_filter(Arc::new(host_filter));
+    }
 
     async move {
+        if peer_identity_verifier
+            .as_ref()
+            .map_or(false, |verifier| verifier.verifies_hostname())
+            && !resolve_config.use_hostname_resolution
+        {
+            return Err((
+                CassError::CASS_ERROR_LIB_BAD_PARAMS,
+                "CASS_SSL_VERIFY_PEER_IDENTITY_DNS requires hostname resolution, \
+                 see cass_cluster_set_use_hostname_resolution()"
+                    .to_string(),
+            ));
+        }
+
+        // Connections whose TLS handshake depends on the node go through tunnels, see `tls_tunnel`.
+        // The driver only ever sees the endpoints of the tunnels, so it cannot re-resolve
+        // the hostnames of the contact points, and has to connect to the nodes' regular
+        // port, as the shard of a connection would be chosen by the port of the tunnel.
+        let tunnel_ssl_context = match (&cloud_config, &peer_identity_verifier) {
+            (Some(cloud_config), _) => Some(cloud_config.ssl_context.clone()),
+            (None, Some(_)) => session_builder.config.ssl_context.take(),
+            (None, None) => None,
+        };
+        let tunnels = tunnel_ssl_context.map(|ssl_context| {
+            session_builder.config.ssl_context = None;
+            Arc::new(TlsTunnels::new(
+                ssl_context,
+                peer_identity_verifier,
+                resolve_config.clone(),
+                &session_builder.config,
+            ))
+        });
+
+        match (cloud_config, tunnels) {
+            (Some(cloud_config), Some(tunnels)) => {
+                let (endpoints, sni_proxy, local_dc) =
+                    connect_cloud(cloud_config, &resolve_config, &tunnels).await?;
+                if let (None, None, Some(local_dc)) = (
+                    &load_balancing_config.dc_awareness,
+                    &load_balancing_config.rack_awareness,
+                    local_dc,
+                ) {
+                    load_balancing_config.dc_awareness = Some(DcAwareness::new(local_dc));
+                }
+                session_builder = session_builder
+                    .known_nodes_addr(&endpoints)
+                    .address_translator(Arc::new(TunnelAddressTranslator::new(
+                        None,
+                        tunnels,
+                        Some(sni_proxy),
+                    )))
+                    .disallow_shard_aware_port(true);
+            }
+            (_, Some(tunnels)) => {
+                let (_, addresses) =
+                    resolve_known_nodes(&contact_points, port, &resolve_config).await;
+                let endpoints = addresses
+                    .into_iter()
+                    .map(|address| tunnels.endpoint(address, None))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|err| {
+                        (
+                            CassError::CASS_ERROR_LIB_UNABLE_TO_INIT,
+                            format!("Unable to open a tunnel to a contact point: {}", err),
+                        )
+                    })?;
+                session_builder = session_builder
+                    .known_nodes_addr(&endpoints)
+                    .address_translator(Arc::new(TunnelAddressTranslator::new(
+                        address_translator,
+                        tunnels,
+                        None,
+                    )))
+                    .disallow_shard_aware_port(true);
+            }
+            (_, None) => {
+                let (known_nodes, _) =
+                    resolve_known_nodes(&contact_points, port, &resolve_config).await;
+                if let Some(address_translator) = address_translator {
+                    session_builder = session_builder.address_translator(address_translator);
+                }
+                session_builder = session_builder.known_nodes(&known_nodes);
+            }
+        }
         let load_balancing = load_balancing_config.clone().build().await;
         execution_profile_builder = execution_profile_builder.load_balancing_policy(load_balancing);
-        session_builder
-            .default_execution_profile_handle(execution_profile_builder.build().into_handle())
+        Ok(session_builder
+            .default_execution_profile_handle(execution_profile_builder.build().into_handle()))
+    }
+}
+
+// Asks the metadata service of a cloud cluster for its SNI proxy and the nodes
+// behind it. Every node is reached through the proxy, which is told the host ID
+// of the node to route to, so every host ID is given a tunnel of its own. Returns
+// the endpoints of the tunnels to the nodes known from the metadata, the address
+// of the proxy and the local datacenter.
+async fn connect_cloud(
+    cloud_config: Arc<CloudConfig>,
+    resolve_config: &ResolveConfig,
+    tunnels: &TlsTunnels,
+) -> Result<(Vec<SocketAddr>, SocketAddr, Option<String>), (CassError, String)> {
+    let timeout = resolve_config.timeout;
+    let fetch_config = cloud_config.clone();
+    // The metadata service is queried with blocking I/O.
+    let metadata = tokio::task::spawn_blocking(move || fetch_config.fetch_metadata(timeout))
+        .await
+        .map_err(|err| err.to_string())
+        .and_then(|result| result)
+        .map_err(|err| {
+            (
+                CassError::CASS_ERROR_LIB_NO_HOSTS_AVAILABLE,
+                format!("Unable to fetch cloud metadata: {}", err),
+            )
+        })?;
+
+    let unresolved_proxy = || {
+        (
+            CassError::CASS_ERROR_LIB_NO_HOSTS_AVAILABLE,
+            format!("Unable to resolve SNI proxy {}", metadata.sni_proxy_address),
+        )
+    };
+    let lookup = tokio::net::lookup_host(metadata.sni_proxy_address.clone());
+    let sni_proxy = match tokio::time::timeout(timeout, lookup).await {
+        Ok(Ok(mut addresses)) => addresses.next().ok_or_else(unresolved_proxy)?,
+        _ => return Err(unresolved_proxy()),
+    };
+
+    let endpoints = metadata
+        .host_ids
+        .iter()
+        .map(|host_id| tunnels.endpoint(sni_proxy, Some(host_id.clone())))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| {
+            (
+                CassError::CASS_ERROR_LIB_UNABLE_TO_INIT,
+                format!("Unable to open a tunnel to the SNI proxy: {}", err),
+            )
+        })?;
+
+    Ok((endpoints, sni_proxy, metadata.local_dc))
+}
+
+// In SRV mode, a hostname without a port is replaced with the targets of its SRV
+// records, or used as a plain hostname if it has none. Contact points are resolved
+// beforehand, so that every lookup is bounded by the resolve timeout, and the ones
+// that cannot be resolved are skipped. The others are handed to the driver as they
+// are, along with the addresses they resolve to: the driver resolves hostnames once
+// again if it loses every node of the cluster, to follow the cluster to new addresses.
+async fn resolve_known_nodes(
+    contact_points: &[ContactPoint],
+    port: u16,
+    resolve_config: &ResolveConfig,
+) -> (Vec<String>, Vec<SocketAddr>) {
+    let mut hosts = Vec::with_capacity(contact_points.len());
+    for contact_point in contact_points {
+        if let Some(name) = contact_point
+            .srv_name()
+            .filter(|_| resolve_config.use_srv_records)
+        {
+            match resolve_srv(name, resolve_config).await {
+                Ok(records) if !records.is_empty() => {
+                    hosts.extend(records.into_iter().map(|record| ContactPoint {
+                        host: record.target,
+                        port: Some(record.port),
+                    }));
+                    continue;
+                }
+                Ok(_) => tracing::warn!("No SRV records found for {}", name),
+                Err(err) => tracing::warn!("{}", err),
+            }
+        }
+        hosts.push(contact_point.clone());
+    }
+
+    let mut known_nodes: Vec<String> = Vec::with_capacity(hosts.len());
+    let mut addresses: Vec<SocketAddr> = Vec::with_capacity(hosts.len());
+    for host in hosts {
+        let known_node = host.known_node(port);
+        if known_nodes.contains(&known_node) {
+            continue;
+        }
+        match resolve_host(&host.host, host.port.unwrap_or(port), resolve_config).await {
+            Ok(resolved) if !resolved.is_empty() => {
+                known_nodes.push(known_node);
+                for address in resolved {
+                    if !addresses.contains(&address) {
+                        addresses.push(address);
+                    }
+                }
+            }
+            Ok(_) => tracing::warn!("No addresses found for contact point {}", known_node),
+            Err(err) => tracing::warn!("Unable to resolve contact point {}: {}", known_node, err),
+        }
     }
+    (known_nodes, addresses)
 }
 
 #[no_mangle]
@@ -119,15 +517,31 @@ pub unsafe extern "C" fn cass_cluster_new() -> *mut CassCluster {
     let default_execution_profile_builder =
         ExecutionProfileBuilder::default().consistency(Consistency::LocalOne);
 
+    let mut session_builder = SessionBuilder::new();
+    // Heartbeat defaults of the CPP driver.
+    session_builder.config.keepalive_interval = Some(Duration::from_secs(30));
+    session_builder.config.keepalive_timeout = Some(Duration::from_secs(60));
+
     Box::into_raw(Box::new(CassCluster {
-        session_builder: SessionBuilder::new(),
+        session_builder,
         port: 9042,
         contact_points: Vec::new(),
+        resolve_config: Default::default(),
         // Per DataStax documentation: Without additional configuration the C/C++ driver
         // defaults to using Datacenter-aware load balancing with token-aware routing.
         use_beta_protocol_version: false,
         auth_username: None,
         auth_password: None,
+        authenticator_provider: None,
+        peer_identity_verifier: None,
+        ssl: None,
+        address_translator: None,
+        cloud_config: None,
+        pool_size: None,
+        request_throttling_config: Default::default(),
+        timestamp_generator: Arc::new(TimestampGenerator::Monotonic(
+            MonotonicTimestampGenerator::default(),
+        )),
         default_execution_profile_builder,
         execution_profile_map: Default::default(),
         load_balancing_config: Default::default(),
@@ -177,16 +591,48 @@ unsafe fn cluster_set_contact_points(
         return Ok(());
     }
 
+    // A malformed entry rejects the whole list, so that no contact point is added.
+    let contact_points = contact_points
+        .map(str::trim)
+        .filter(|cp| !cp.is_empty())
+        .map(|cp| ContactPoint::parse(cp).ok_or(CassError::CASS_ERROR_LIB_BAD_PARAMS))
+        .collect::<Result<Vec<_>, _>>()?;
+
     // cass_cluster_set_contact_points() will append
     // in subsequent calls, not overwrite.
-    cluster.contact_points.extend(
-        contact_points
-            .map(|cp| cp.trim().to_string())
-            .filter(|cp| !cp.is_empty()),
-    );
+    cluster.contact_points.extend(contact_points);
     Ok(())
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_resolve_timeout(
+    cluster_raw: *mut CassCluster,
+    timeout_ms: c_uint,
+) {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    cluster.resolve_config.timeout = Duration::from_millis(timeout_ms.into());
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_use_hostname_resolution(
+    cluster_raw: *mut CassCluster,
+    enabled: cass_bool_t,
+) -> CassError {
+    // Hostnames are only needed by CASS_SSL_VERIFY_PEER_IDENTITY_DNS.
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    cluster.resolve_config.use_hostname_resolution = enabled != 0;
+    CassError::CASS_OK
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_use_srv_records(
+    cluster_raw: *mut CassCluster,
+    enabled: cass_bool_t,
+) {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    cluster.resolve_config.use_srv_records = enabled != 0;
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn cass_cluster_set_use_randomized_contact_points(
     _cluster_raw: *mut CassCluster,
@@ -215,6 +661,29 @@ pub unsafe extern "C" fn cass_cluster_set_tcp_nodelay(
     cluster.session_builder.config.tcp_nodelay = enabled != 0;
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_tcp_keepalive(
+    cluster_raw: *mut CassCluster,
+    enabled: cass_bool_t,
+    delay_secs: c_uint,
+) {
+    // A zero delay would make the socket probe the peer continuously,
+    // so the shortest delay accepted by the OS is used instead.
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    cluster.session_builder.config.tcp_keepalive_interval =
+        (enabled != 0).then(|| Duration::from_secs(delay_secs.max(1).into()));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_timestamp_gen(
+    cluster_raw: *mut CassCluster,
+    timestamp_gen_raw: *const CassTimestampGen,
+) {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    // The cluster shares the generator, so it may be freed right after this call.
+    cluster.timestamp_generator = clone_arced(timestamp_gen_raw);
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn cass_cluster_set_connect_timeout(
     cluster_raw: *mut CassCluster,
@@ -224,6 +693,141 @@ pub unsafe extern "C" fn cass_cluster_set_connect_timeout(
     cluster.session_builder.config.connect_timeout = Duration::from_millis(timeout_ms.into());
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_connection_heartbeat_interval(
+    cluster_raw: *mut CassCluster,
+    interval_secs: c_uint,
+) {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    cluster.session_builder.config.keepalive_interval = match interval_secs {
+        0 => None,
+        _ => Some(Duration::from_secs(interval_secs.into())),
+    };
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_connection_idle_timeout(
+    cluster_raw: *mut CassCluster,
+    timeout_secs: c_uint,
+) {
+    // Heartbeats left unanswered for this long make the connection defunct,
+    // so that it is closed and reopened by the pool.
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    cluster.session_builder.config.keepalive_timeout = match timeout_secs {
+        0 => None,
+        _ => Some(Duration::from_secs(timeout_secs.into())),
+    };
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_core_connections_per_host(
+    cluster_raw: *mut CassCluster,
+    num_connections: c_uint,
+) -> CassError {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    let num_connections = match NonZeroUsize::new(num_connections as usize) {
+        Some(num_connections) => num_connections,
+        None => return CassError::CASS_ERROR_LIB_BAD_PARAMS,
+    };
+
+    cluster.pool_size = Some(PoolSize::PerHost(num_connections));
+
+    CassError::CASS_OK
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_max_connections_per_host(
+    _cluster_raw: *mut CassCluster,
+    num_connections: c_uint,
+) -> CassError {
+    if num_connections == 0 {
+        return CassError::CASS_ERROR_LIB_BAD_PARAMS;
+    }
+
+    // The Rust driver keeps a fixed number of connections per host, set with
+    // cass_cluster_set_core_connections_per_host(), and never opens more of them.
+    tracing::warn!(
+        "cass_cluster_set_max_connections_per_host() is deprecated and has no effect, \
+        the setting of {} connections is ignored",
+        num_connections
+    );
+
+    CassError::CASS_OK
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_max_concurrent_creation(
+    _cluster_raw: *mut CassCluster,
+    num_connections: c_uint,
+) -> CassError {
+    if num_connections == 0 {
+        return CassError::CASS_ERROR_LIB_BAD_PARAMS;
+    }
+
+    // The Rust driver opens all connections of a pool at once, so there is nothing to configure.
+    tracing::warn!(
+        "cass_cluster_set_max_concurrent_creation() is deprecated and has no effect, \
+        the setting of {} connections is ignored",
+        num_connections
+    );
+
+    CassError::CASS_OK
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_max_concurrent_requests_threshold(
+    cluster_raw: *mut CassCluster,
+    num_requests: c_uint,
+) -> CassError {
+    let max_concurrent_requests = match NonZeroUsize::new(num_requests as usize) {
+        Some(max_concurrent_requests) => max_concurrent_requests,
+        None => return CassError::CASS_ERROR_LIB_BAD_PARAMS,
+    };
+
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    cluster.request_throttling_config.max_concurrent_requests = Some(max_concurrent_requests);
+    CassError::CASS_OK
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_pending_requests_high_water_mark(
+    cluster_raw: *mut CassCluster,
+    num_requests: c_uint,
+) -> CassError {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    let high_water_mark = match NonZeroUsize::new(num_requests as usize) {
+        Some(high_water_mark) => high_water_mark,
+        None => return CassError::CASS_ERROR_LIB_BAD_PARAMS,
+    };
+    if let Some(low_water_mark) = cluster.request_throttling_config.low_water_mark {
+        if high_water_mark.get() < low_water_mark {
+            // High water mark cannot be lower than low water mark
+            return CassError::CASS_ERROR_LIB_BAD_PARAMS;
+        }
+    }
+
+    cluster.request_throttling_config.high_water_mark = Some(high_water_mark);
+    CassError::CASS_OK
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_pending_requests_low_water_mark(
+    cluster_raw: *mut CassCluster,
+    num_requests: c_uint,
+) -> CassError {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    let low_water_mark = num_requests as usize;
+    if let Some(high_water_mark) = cluster.request_throttling_config.high_water_mark {
+        if low_water_mark > high_water_mark.get() {
+            // Low water mark cannot be greater than high water mark
+            return CassError::CASS_ERROR_LIB_BAD_PARAMS;
+        }
+    }
+
+    cluster.request_throttling_config.low_water_mark = Some(low_water_mark);
+    CassError::CASS_OK
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn cass_cluster_set_port(
     cluster_raw: *mut CassCluster,
@@ -268,12 +872,408 @@ pub unsafe extern "C" fn cass_cluster_set_credentials_n(
     let cluster = ptr_to_ref_mut(cluster_raw);
     cluster.auth_username = Some(username.to_string());
     cluster.auth_password = Some(password.to_string());
+    // Credentials replace any previously set custom authenticator.
+    cluster.authenticator_provider = None;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_authenticator_callbacks(
+    cluster_raw: *mut CassCluster,
+    exchange_callbacks: *const CassAuthenticatorCallbacks,
+    cleanup_callback: CassAuthenticatorDataCleanupCallback,
+    data: *mut c_void,
+) -> CassError {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    let exchange_callbacks = match exchange_callbacks.as_ref() {
+        Some(callbacks) => *callbacks,
+        None => return CassError::CASS_ERROR_LIB_BAD_PARAMS,
+    };
+
+    cluster.authenticator_provider = Some(new_authenticator_provider(
+        exchange_callbacks,
+        cleanup_callback,
+        data,
+    ));
+    // Custom authenticator replaces any previously set credentials.
+    cluster.auth_username = None;
+    cluster.auth_password = None;
+
+    CassError::CASS_OK
+}
+
+fn cluster_set_credentials_source(cluster: &mut CassCluster, source: CredentialsSource) {
+    cluster.authenticator_provider = Some(new_credentials_provider(source));
+    // Credentials provider replaces any previously set credentials.
+    cluster.auth_username = None;
+    cluster.auth_password = None;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_credentials_callback(
+    cluster_raw: *mut CassCluster,
+    callback: CassCredentialsCallback,
+    cleanup_callback: CassDataCleanupCallback,
+    data: *mut c_void,
+) -> CassError {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    let callback = match callback {
+        Some(callback) => callback,
+        None => return CassError::CASS_ERROR_LIB_BAD_PARAMS,
+    };
+
+    cluster_set_credentials_source(
+        cluster,
+        CredentialsSource::Callback {
+            callback,
+            data_cleanup_callback: cleanup_callback,
+            data,
+        },
+    );
+
+    CassError::CASS_OK
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_credentials_file(
+    cluster: *mut CassCluster,
+    path: *const c_char,
+) -> CassError {
+    cass_cluster_set_credentials_file_n(cluster, path, strlen(path))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_credentials_file_n(
+    cluster_raw: *mut CassCluster,
+    path: *const c_char,
+    path_length: size_t,
+) -> CassError {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    let path = match ptr_to_cstr_n(path, path_length) {
+        Some(path) if !path.is_empty() => path,
+        _ => return CassError::CASS_ERROR_LIB_BAD_PARAMS,
+    };
+
+    cluster_set_credentials_source(cluster, CredentialsSource::File(path.to_string()));
+
+    CassError::CASS_OK
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_credentials_env(
+    cluster: *mut CassCluster,
+    username_variable: *const c_char,
+    password_variable: *const c_char,
+) -> CassError {
+    cass_cluster_set_credentials_env_n(
+        cluster,
+        username_variable,
+        strlen(username_variable),
+        password_variable,
+        strlen(password_variable),
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_credentials_env_n(
+    cluster_raw: *mut CassCluster,
+    username_variable: *const c_char,
+    username_variable_length: size_t,
+    password_variable: *const c_char,
+    password_variable_length: size_t,
+) -> CassError {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    let (username_variable, password_variable) = match (
+        ptr_to_cstr_n(username_variable, username_variable_length),
+        ptr_to_cstr_n(password_variable, password_variable_length),
+    ) {
+        (Some(username_variable), Some(password_variable))
+            if !username_variable.is_empty() && !password_variable.is_empty() =>
+        {
+            (username_variable, password_variable)
+        }
+        _ => return CassError::CASS_ERROR_LIB_BAD_PARAMS,
+    };
+
+    cluster_set_credentials_source(
+        cluster,
+        CredentialsSource::Env {
+            username_variable: username_variable.to_string(),
+            password_variable: password_variable.to_string(),
+        },
+    );
+
+    CassError::CASS_OK
+}
+
+pub(crate) unsafe fn set_host_filtering_n(
+    filtered_hosts: &mut Vec<IpAddr>,
+    hosts_raw: *const c_char,
+    hosts_length: size_t,
+) -> CassError {
+    let hosts = match ptr_to_cstr_n(hosts_raw, hosts_length) {
+        Some(hosts) => hosts,
+        None => return CassError::CASS_ERROR_LIB_BAD_PARAMS,
+    };
+
+    // Empty string clears and disables the filtering.
+    if hosts.trim().is_empty() {
+        filtered_hosts.clear();
+        return CassError::CASS_OK;
+    }
+
+    let mut parsed_hosts = Vec::new();
+    let mut has_invalid_hosts = false;
+    for host in hosts
+        .split(',')
+        .map(str::trim)
+        .filter(|host| !host.is_empty())
+    {
+        match host.parse::<IpAddr>() {
+            Ok(address) => parsed_hosts.push(address),
+            Err(_) => {
+                tracing::error!("Invalid host address in filtering list: {}", host);
+                has_invalid_hosts = true;
+            }
+        }
+    }
+
+    // A list with invalid addresses is rejected as a whole.
+    if has_invalid_hosts {
+        return CassError::CASS_ERROR_LIB_BAD_PARAMS;
+    }
+
+    // Subsequent calls append to the list.
+    filtered_hosts.extend(parsed_hosts);
+    CassError::CASS_OK
+}
+
+// The cluster setters cannot return an error, so a rejected list is only logged.
+fn log_rejected_filtering(result: CassError, filtering: &str) {
+    if result != CassError::CASS_OK {
+        tracing::error!(
+            "The {} of the cluster was rejected and left unchanged",
+            filtering
+        );
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_address_translator_callback(
+    cluster_raw: *mut CassCluster,
+    callback: CassAddressTranslatorCallback,
+    cleanup_callback: CassDataCleanupCallback,
+    data: *mut c_void,
+) -> CassError {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    let callback = match callback {
+        Some(callback) => callback,
+        None => return CassError::CASS_ERROR_LIB_BAD_PARAMS,
+    };
+
+    cluster.address_translator = Some(Arc::new(CassAddressTranslator::new(
+        AddressTranslation::Callback {
+            callback,
+            data_cleanup_callback: cleanup_callback,
+            data,
+        },
+    )));
+
+    CassError::CASS_OK
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_add_address_translation(
+    cluster_raw: *mut CassCluster,
+    address: *const c_char,
+    port: c_int,
+    translated_address: *const c_char,
+    translated_port: c_int,
+) -> CassError {
+    cass_cluster_add_address_translation_n(
+        cluster_raw,
+        address,
+        strlen(address),
+        port,
+        translated_address,
+        strlen(translated_address),
+        translated_port,
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_add_address_translation_n(
+    cluster_raw: *mut CassCluster,
+    address: *const c_char,
+    address_length: size_t,
+    port: c_int,
+    translated_address: *const c_char,
+    translated_address_length: size_t,
+    translated_port: c_int,
+) -> CassError {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    let parse_address =
+        |address: *const c_char, address_length: size_t, port: c_int| -> Option<SocketAddr> {
+            let ip = ptr_to_cstr_n(address, address_length)?.parse().ok()?;
+            let port = u16::try_from(port).ok()?;
+            Some(SocketAddr::new(ip, port))
+        };
+    let (address, translated_address) = match (
+        parse_address(address, address_length, port),
+        parse_address(
+            translated_address,
+            translated_address_length,
+            translated_port,
+        ),
+    ) {
+        (Some(address), Some(translated_address)) => (address, translated_address),
+        _ => return CassError::CASS_ERROR_LIB_BAD_PARAMS,
+    };
+
+    // Translations accumulate in a map, which replaces a previously set callback.
+    let mut translations = match cluster
+        .address_translator
+        .as_deref()
+        .map(CassAddressTranslator::translation)
+    {
+        Some(AddressTranslation::Map(translations)) => translations.clone(),
+        _ => HashMap::new(),
+    };
+    translations.insert(address, translated_address);
+    cluster.address_translator = Some(Arc::new(CassAddressTranslator::new(
+        AddressTranslation::Map(translations),
+    )));
+
+    CassError::CASS_OK
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_whitelist_filtering(
+    cluster: *mut CassCluster,
+    hosts: *const c_char,
+) {
+    cass_cluster_set_whitelist_filtering_n(cluster, hosts, strlen(hosts))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_whitelist_filtering_n(
+    cluster_raw: *mut CassCluster,
+    hosts: *const c_char,
+    hosts_length: size_t,
+) {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+
+    let result = set_host_filtering_n(
+        &mut cluster.load_balancing_config.filtering.whitelist_hosts,
+        hosts,
+        hosts_length,
+    );
+    log_rejected_filtering(result, "host whitelist");
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_blacklist_filtering(
+    cluster: *mut CassCluster,
+    hosts: *const c_char,
+) {
+    cass_cluster_set_blacklist_filtering_n(cluster, hosts, strlen(hosts))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_blacklist_filtering_n(
+    cluster_raw: *mut CassCluster,
+    hosts: *const c_char,
+    hosts_length: size_t,
+) {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+
+    let result = set_host_filtering_n(
+        &mut cluster.load_balancing_config.filtering.blacklist_hosts,
+        hosts,
+        hosts_length,
+    );
+    log_rejected_filtering(result, "host blacklist");
+}
+
+pub(crate) unsafe fn set_dc_filtering_n(
+    filtered_dcs: &mut Vec<String>,
+    dcs_raw: *const c_char,
+    dcs_length: size_t,
+) -> CassError {
+    let dcs = match ptr_to_cstr_n(dcs_raw, dcs_length) {
+        Some(dcs) => dcs,
+        None => return CassError::CASS_ERROR_LIB_BAD_PARAMS,
+    };
+
+    // Empty string clears and disables the filtering.
+    if dcs.trim().is_empty() {
+        filtered_dcs.clear();
+        return CassError::CASS_OK;
+    }
+
+    // Subsequent calls append to the list.
+    filtered_dcs.extend(
+        dcs.split(',')
+            .map(str::trim)
+            .filter(|dc| !dc.is_empty())
+            .map(ToOwned::to_owned),
+    );
+
+    CassError::CASS_OK
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_whitelist_dc_filtering(
+    cluster: *mut CassCluster,
+    dcs: *const c_char,
+) {
+    cass_cluster_set_whitelist_dc_filtering_n(cluster, dcs, strlen(dcs))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_whitelist_dc_filtering_n(
+    cluster_raw: *mut CassCluster,
+    dcs: *const c_char,
+    dcs_length: size_t,
+) {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+
+    let result = set_dc_filtering_n(
+        &mut cluster.load_balancing_config.filtering.whitelist_dcs,
+        dcs,
+        dcs_length,
+    );
+    log_rejected_filtering(result, "datacenter whitelist");
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_blacklist_dc_filtering(
+    cluster: *mut CassCluster,
+    dcs: *const c_char,
+) {
+    cass_cluster_set_blacklist_dc_filtering_n(cluster, dcs, strlen(dcs))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_blacklist_dc_filtering_n(
+    cluster_raw: *mut CassCluster,
+    dcs: *const c_char,
+    dcs_length: size_t,
+) {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+
+    let result = set_dc_filtering_n(
+        &mut cluster.load_balancing_config.filtering.blacklist_dcs,
+        dcs,
+        dcs_length,
+    );
+    log_rejected_filtering(result, "datacenter blacklist");
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn cass_cluster_set_load_balance_round_robin(cluster_raw: *mut CassCluster) {
     let cluster = ptr_to_ref_mut(cluster_raw);
     cluster.load_balancing_config.dc_awareness = None;
+    cluster.load_balancing_config.rack_awareness = None;
 }
 
 #[no_mangle]
@@ -303,51 +1303,216 @@ pub(crate) unsafe fn set_load_balance_dc_aware_n(
         return CassError::CASS_ERROR_LIB_BAD_PARAMS;
     }
 
-    if used_hosts_per_remote_dc != 0 || allow_remote_dcs_for_local_cl != 0 {
-        // TODO: Add warning that the parameters are deprecated and not supported in the driver.
+    let local_dc = ptr_to_cstr_n(local_dc_raw, local_dc_length)
+        .unwrap()
+        .to_string();
+
+    load_balancing_config.dc_awareness = Some(DcAwareness {
+        local_dc,
+        used_hosts_per_remote_dc,
+        allow_remote_dcs_for_local_cl: allow_remote_dcs_for_local_cl != 0,
+    });
+    load_balancing_config.rack_awareness = None;
+
+    CassError::CASS_OK
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_load_balance_dc_aware_n(
+    cluster_raw: *mut CassCluster,
+    local_dc_raw: *const c_char,
+    local_dc_length: size_t,
+    used_hosts_per_remote_dc: c_uint,
+    allow_remote_dcs_for_local_cl: cass_bool_t,
+) -> CassError {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+
+    set_load_balance_dc_aware_n(
+        &mut cluster.load_balancing_config,
+        local_dc_raw,
+        local_dc_length,
+        used_hosts_per_remote_dc,
+        allow_remote_dcs_for_local_cl,
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_load_balance_rack_aware(
+    cluster: *mut CassCluster,
+    local_dc: *const c_char,
+    local_rack: *const c_char,
+) -> CassError {
+    cass_cluster_set_load_balance_rack_aware_n(
+        cluster,
+        local_dc,
+        strlen(local_dc),
+        local_rack,
+        strlen(local_rack),
+    )
+}
+
+pub(crate) unsafe fn set_load_balance_rack_aware_n(
+    load_balancing_config: &mut LoadBalancingConfig,
+    local_dc_raw: *const c_char,
+    local_dc_length: size_t,
+    local_rack_raw: *const c_char,
+    local_rack_length: size_t,
+) -> CassError {
+    if local_dc_raw.is_null()
+        || local_dc_length == 0
+        || local_rack_raw.is_null()
+        || local_rack_length == 0
+    {
         return CassError::CASS_ERROR_LIB_BAD_PARAMS;
     }
 
     let local_dc = ptr_to_cstr_n(local_dc_raw, local_dc_length)
         .unwrap()
         .to_string();
+    let local_rack = ptr_to_cstr_n(local_rack_raw, local_rack_length)
+        .unwrap()
+        .to_string();
 
-    load_balancing_config.dc_awareness = Some(DcAwareness { local_dc });
+    load_balancing_config.rack_awareness = Some(RackAwareness {
+        local_dc,
+        local_rack,
+    });
+    load_balancing_config.dc_awareness = None;
 
     CassError::CASS_OK
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn cass_cluster_set_load_balance_dc_aware_n(
+pub unsafe extern "C" fn cass_cluster_set_load_balance_rack_aware_n(
+    cluster_raw: *mut CassCluster,
+    local_dc_raw: *const c_char,
+    local_dc_length: size_t,
+    local_rack_raw: *const c_char,
+    local_rack_length: size_t,
+) -> CassError {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+
+    set_load_balance_rack_aware_n(
+        &mut cluster.load_balancing_config,
+        local_dc_raw,
+        local_dc_length,
+        local_rack_raw,
+        local_rack_length,
+    )
+}
+
+pub(crate) fn set_load_balance_custom(
+    load_balancing_config: &mut LoadBalancingConfig,
+    callback: CassLoadBalancingCallback,
+    cleanup_callback: CassDataCleanupCallback,
+    data: *mut c_void,
+) -> CassError {
+    let callback = match callback {
+        Some(callback) => callback,
+        None => return CassError::CASS_ERROR_LIB_BAD_PARAMS,
+    };
+
+    load_balancing_config.custom_policy = Some(Arc::new(CustomPolicyCallback::new(
+        callback,
+        cleanup_callback,
+        data,
+    )));
+
+    CassError::CASS_OK
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_load_balance_custom(
+    cluster_raw: *mut CassCluster,
+    callback: CassLoadBalancingCallback,
+    cleanup_callback: CassDataCleanupCallback,
+    data: *mut c_void,
+) -> CassError {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+
+    set_load_balance_custom(
+        &mut cluster.load_balancing_config,
+        callback,
+        cleanup_callback,
+        data,
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_cloud_secure_connection_bundle(
+    cluster_raw: *mut CassCluster,
+    path: *const c_char,
+) -> CassError {
+    cass_cluster_set_cloud_secure_connection_bundle_n(cluster_raw, path, strlen(path))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_cloud_secure_connection_bundle_n(
     cluster_raw: *mut CassCluster,
-    local_dc_raw: *const c_char,
-    local_dc_length: size_t,
-    used_hosts_per_remote_dc: c_uint,
-    allow_remote_dcs_for_local_cl: cass_bool_t,
+    path: *const c_char,
+    path_length: size_t,
 ) -> CassError {
-    let cluster = ptr_to_ref_mut(cluster_raw);
+    openssl_sys::init();
+    cass_cluster_set_cloud_secure_connection_bundle_no_ssl_lib_init_n(
+        cluster_raw,
+        path,
+        path_length,
+    )
+}
 
-    set_load_balance_dc_aware_n(
-        &mut cluster.load_balancing_config,
-        local_dc_raw,
-        local_dc_length,
-        used_hosts_per_remote_dc,
-        allow_remote_dcs_for_local_cl,
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_cloud_secure_connection_bundle_no_ssl_lib_init(
+    cluster_raw: *mut CassCluster,
+    path: *const c_char,
+) -> CassError {
+    cass_cluster_set_cloud_secure_connection_bundle_no_ssl_lib_init_n(
+        cluster_raw,
+        path,
+        strlen(path),
     )
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn cass_cluster_set_cloud_secure_connection_bundle_n(
-    _cluster_raw: *mut CassCluster,
+pub unsafe extern "C" fn cass_cluster_set_cloud_secure_connection_bundle_no_ssl_lib_init_n(
+    cluster_raw: *mut CassCluster,
     path: *const c_char,
     path_length: size_t,
 ) -> CassError {
-    // FIXME: Should unzip file associated with the path
-    let zip_file = ptr_to_cstr_n(path, path_length).unwrap();
+    let path = match ptr_to_cstr_n(path, path_length) {
+        Some(path) => path,
+        None => return CassError::CASS_ERROR_LIB_BAD_PARAMS,
+    };
+    let config = match CloudConfig::from_bundle(path) {
+        Ok(config) => config,
+        Err(err) => return err,
+    };
 
-    if zip_file == "invalid_filename" {
-        return CassError::CASS_ERROR_LIB_BAD_PARAMS;
+    let cluster = ptr_to_ref_mut(cluster_raw);
+
+    // The contact points are taken from the metadata service of the cloud cluster
+    // when connecting, see `connect_cloud()`.
+    cluster.contact_points = Vec::new();
+    cluster.session_builder.config.ssl_context = Some(config.ssl_context.clone());
+    cluster.peer_identity_verifier = None;
+    cluster.ssl = None;
+
+    // Credentials from the bundle are only a fallback for the ones set explicitly.
+    if cluster.auth_username.is_none() && cluster.authenticator_provider.is_none() {
+        if let (Some(username), Some(password)) = (config.username.clone(), config.password.clone())
+        {
+            cluster.auth_username = Some(username);
+            cluster.auth_password = Some(password);
+        }
     }
+    let load_balancing_config = &mut cluster.load_balancing_config;
+    if let (None, None, Some(local_dc)) = (
+        &load_balancing_config.dc_awareness,
+        &load_balancing_config.rack_awareness,
+        config.local_dc.clone(),
+    ) {
+        load_balancing_config.dc_awareness = Some(DcAwareness::new(local_dc));
+    }
+    cluster.cloud_config = Some(Arc::new(config));
 
     CassError::CASS_OK
 }
@@ -373,17 +1538,22 @@ pub unsafe extern "C" fn cass_cluster_set_exponential_reconnect(
         return CassError::CASS_ERROR_LIB_BAD_PARAMS;
     }
 
-    // FIXME: should set exponential reconnect with base_delay_ms and max_delay_ms
-    /*
-    cluster->config().set_exponential_reconnect(base_delay_ms, max_delay_ms);
-    */
+    // The connection pools of the Rust driver refill with their own backoff,
+    // which cannot be configured.
+    tracing::warn!(
+        "Exponential reconnection policy (base delay {} ms, max delay {} ms) is not supported \
+        and is ignored",
+        base_delay_ms,
+        max_delay_ms
+    );
 
     CassError::CASS_OK
 }
 
+// The Rust driver neither sends custom payloads with requests nor exposes the ones
+// received with responses, so there are no payloads to create and none to read.
 #[no_mangle]
 pub extern "C" fn cass_custom_payload_new() -> *const CassCustomPayload {
-    // FIXME: should create a new custom payload that must be freed
     std::ptr::null()
 }
 
@@ -396,7 +1566,7 @@ pub extern "C" fn cass_future_custom_payload_item(
     _value: *const cass_byte_t,
     _value_size: size_t,
 ) -> CassError {
-    CassError::CASS_OK
+    CassError::CASS_ERROR_LIB_INDEX_OUT_OF_BOUNDS
 }
 
 #[no_mangle]
@@ -485,6 +1655,15 @@ pub unsafe extern "C" fn cass_cluster_set_token_aware_routing(
     cluster.load_balancing_config.token_awareness_enabled = enabled != 0;
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn cass_cluster_set_token_aware_routing_shuffle_replicas(
+    cluster_raw: *mut CassCluster,
+    enabled: cass_bool_t,
+) {
+    let cluster = ptr_to_ref_mut(cluster_raw);
+    cluster.load_balancing_config.token_aware_shuffling_enabled = enabled != 0;
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn cass_cluster_set_retry_policy(
     cluster_raw: *mut CassCluster,
@@ -513,6 +1692,8 @@ pub unsafe extern "C" fn cass_cluster_set_ssl(cluster: *mut CassCluster, ssl: *m
     SSL_CTX_up_ref(cass_ssl.ssl_context);
 
     cluster_from_raw.session_builder.config.ssl_context = Some(ssl_context_builder.build());
+    cluster_from_raw.peer_identity_verifier = Some(cass_ssl.peer_identity_verifier.clone());
+    cluster_from_raw.ssl = Some(cass_ssl);
 }
 
 #[no_mangle]
@@ -636,7 +1817,19 @@ mod tests {
     use crate::{
         argconv::make_c_str,
         cass_error::CassError,
-        exec_profile::{cass_execution_profile_free, cass_execution_profile_new},
+        exec_profile::{
+            cass_execution_profile_free, cass_execution_profile_new,
+            cass_execution_profile_set_blacklist_dc_filtering,
+            cass_execution_profile_set_blacklist_filtering,
+            cass_execution_profile_set_whitelist_dc_filtering,
+            cass_execution_profile_set_whitelist_filtering,
+        },
+        inet::CassInet,
+        resolver::{tests::StubResolver, SrvRecord},
+        timestamp_generator::{
+            cass_timestamp_gen_free, cass_timestamp_gen_monotonic_new_with_settings,
+            cass_timestamp_gen_server_side_new,
+        },
     };
     use assert_matches::assert_matches;
     use std::{
@@ -645,6 +1838,159 @@ mod tests {
         os::raw::c_char,
     };
 
+    #[test]
+    #[ntest::timeout(100)]
+    fn test_contact_points() {
+        unsafe {
+            let cluster_raw = cass_cluster_new();
+            {
+                let cluster = ptr_to_ref(cluster_raw);
+                let known_nodes = || {
+                    cluster
+                        .contact_points
+                        .iter()
+                        .map(|cp| cp.known_node(cluster.port))
+                        .collect::<Vec<_>>()
+                };
+
+                assert_cass_error_eq!(
+                    cass_cluster_set_contact_points(
+                        cluster_raw,
+                        make_c_str!("127.0.0.1, 127.0.0.2:19042,::1, [::2], [::3]:19042,node-1.example.com,localhost:9043")
+                    ),
+                    CassError::CASS_OK
+                );
+                assert_eq!(
+                    known_nodes(),
+                    vec![
+                        "127.0.0.1:9042",
+                        "127.0.0.2:19042",
+                        "[::1]:9042",
+                        "[::2]:9042",
+                        "[::3]:19042",
+                        "node-1.example.com:9042",
+                        "localhost:9043",
+                    ]
+                );
+
+                // Only contact points without a port follow the port of the cluster
+                assert_cass_error_eq!(cass_cluster_set_port(cluster_raw, 9000), CassError::CASS_OK);
+                assert_eq!(known_nodes()[0], "127.0.0.1:9000");
+                assert_eq!(known_nodes()[1], "127.0.0.2:19042");
+                assert_eq!(known_nodes()[2], "[::1]:9000");
+
+                // Malformed entries reject the whole list
+                for contact_points in [
+                    "127.0.0.5, 127.0.0.1:",
+                    "127.0.0.5, 127.0.0.1:port",
+                    "127.0.0.5, 127.0.0.1:65536",
+                    "127.0.0.5, 127.0.0.1:0",
+                    "127.0.0.5, [::1",
+                    "127.0.0.5, [::1]9042",
+                    "127.0.0.5, [127.0.0.1]:9042",
+                    "127.0.0.5, 127.0.0.1:9042:1",
+                    "127.0.0.5, :9042",
+                    "127.0.0.5, host name",
+                ] {
+                    let (c_contact_points, c_contact_points_len) = str_to_c_str_n(contact_points);
+                    assert_cass_error_eq!(
+                        cass_cluster_set_contact_points_n(
+                            cluster_raw,
+                            c_contact_points,
+                            c_contact_points_len
+                        ),
+                        CassError::CASS_ERROR_LIB_BAD_PARAMS
+                    );
+                }
+                assert_eq!(cluster.contact_points.len(), 7);
+            }
+            cass_cluster_free(cluster_raw);
+        }
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(5000)]
+    async fn test_srv_contact_points() {
+        let resolver = StubResolver::run().await;
+        resolver.set_srv_records(
+            "_cql._tcp.example.com",
+            vec![SrvRecord {
+                priority: 10,
+                weight: 10,
+                port: 19042,
+                target: "localhost".to_string(),
+            }],
+        );
+        let contact_points = [
+            "_cql._tcp.example.com",
+            "localhost:9043",
+            "127.0.0.1",
+            "unknown.invalid",
+        ]
+        .iter()
+        .map(|cp| ContactPoint::parse(cp).unwrap())
+        .collect::<Vec<_>>();
+        let mut resolve_config = resolver.config();
+        let known_nodes = |(known_nodes, _): (Vec<String>, Vec<SocketAddr>)| known_nodes;
+
+        // Without SRV mode, every contact point is a plain hostname.
+        // The ones that cannot be resolved are skipped.
+        assert_eq!(
+            known_nodes(resolve_known_nodes(&contact_points, 9042, &resolve_config).await),
+            vec!["localhost:9043", "127.0.0.1:9042"]
+        );
+
+        // Hostnames are handed to the driver as they are, with the addresses they resolve to.
+        resolve_config.use_srv_records = true;
+        let (srv_known_nodes, addresses) =
+            resolve_known_nodes(&contact_points, 9042, &resolve_config).await;
+        assert_eq!(
+            srv_known_nodes,
+            vec!["localhost:19042", "localhost:9043", "127.0.0.1:9042"]
+        );
+        assert!(addresses.contains(&addr("127.0.0.1:9042")));
+        assert!(addresses
+            .iter()
+            .all(|address| address.ip().is_loopback()
+                && [19042, 9043, 9042].contains(&address.port())));
+
+        // Unreachable name server - SRV names are plain hostnames again
+        let silent = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        resolve_config.nameservers = vec![silent.local_addr().unwrap()];
+        resolve_config.timeout = Duration::from_millis(100);
+        assert_eq!(
+            known_nodes(resolve_known_nodes(&contact_points, 9042, &resolve_config).await),
+            vec!["localhost:9043", "127.0.0.1:9042"]
+        );
+    }
+
+    fn addr(addr: &str) -> SocketAddr {
+        addr.parse().unwrap()
+    }
+
+    #[test]
+    #[ntest::timeout(100)]
+    fn test_resolve_settings() {
+        unsafe {
+            let cluster_raw = cass_cluster_new();
+            {
+                let cluster = ptr_to_ref(cluster_raw);
+                assert_eq!(cluster.resolve_config, ResolveConfig::default());
+
+                cass_cluster_set_resolve_timeout(cluster_raw, 500);
+                cass_cluster_set_use_srv_records(cluster_raw, true as cass_bool_t);
+                assert_cass_error_eq!(
+                    cass_cluster_set_use_hostname_resolution(cluster_raw, true as cass_bool_t),
+                    CassError::CASS_OK
+                );
+                assert_eq!(cluster.resolve_config.timeout, Duration::from_millis(500));
+                assert!(cluster.resolve_config.use_srv_records);
+                assert!(cluster.resolve_config.use_hostname_resolution);
+            }
+            cass_cluster_free(cluster_raw);
+        }
+    }
+
     #[test]
     #[ntest::timeout(100)]
     fn test_load_balancing_config() {
@@ -656,10 +2002,12 @@ mod tests {
                 {
                     assert_matches!(cluster.load_balancing_config.dc_awareness, None);
                     assert!(cluster.load_balancing_config.token_awareness_enabled);
+                    assert!(cluster.load_balancing_config.token_aware_shuffling_enabled);
                     assert!(!cluster.load_balancing_config.latency_awareness_enabled);
                 }
                 {
                     cass_cluster_set_token_aware_routing(cluster_raw, 0);
+                    cass_cluster_set_token_aware_routing_shuffle_replicas(cluster_raw, 0);
                     assert_cass_error_eq!(
                         cass_cluster_set_load_balance_dc_aware(
                             cluster_raw,
@@ -683,33 +2031,454 @@ mod tests {
 
                     let dc_awareness = cluster.load_balancing_config.dc_awareness.as_ref().unwrap();
                     assert_eq!(dc_awareness.local_dc, "eu");
+                    assert_eq!(dc_awareness.used_hosts_per_remote_dc, 0);
+                    assert!(!dc_awareness.allow_remote_dcs_for_local_cl);
                     assert!(!cluster.load_balancing_config.token_awareness_enabled);
+                    assert!(!cluster.load_balancing_config.token_aware_shuffling_enabled);
                     assert!(cluster.load_balancing_config.latency_awareness_enabled);
                 }
-                /* Test invalid configurations */
                 {
-                    // Nonzero deprecated parameters
+                    // Remote datacenters settings
                     assert_cass_error_eq!(
                         cass_cluster_set_load_balance_dc_aware(
                             cluster_raw,
                             "eu\0".as_ptr() as *const i8,
-                            1,
-                            0
+                            2,
+                            1
+                        ),
+                        CassError::CASS_OK
+                    );
+
+                    let dc_awareness = cluster.load_balancing_config.dc_awareness.as_ref().unwrap();
+                    assert_eq!(dc_awareness.local_dc, "eu");
+                    assert_eq!(dc_awareness.used_hosts_per_remote_dc, 2);
+                    assert!(dc_awareness.allow_remote_dcs_for_local_cl);
+                }
+                {
+                    // Rack awareness replaces DC awareness
+                    assert_cass_error_eq!(
+                        cass_cluster_set_load_balance_rack_aware(
+                            cluster_raw,
+                            "eu\0".as_ptr() as *const i8,
+                            "rack1\0".as_ptr() as *const i8,
+                        ),
+                        CassError::CASS_OK
+                    );
+
+                    let rack_awareness = cluster
+                        .load_balancing_config
+                        .rack_awareness
+                        .as_ref()
+                        .unwrap();
+                    assert_eq!(rack_awareness.local_dc, "eu");
+                    assert_eq!(rack_awareness.local_rack, "rack1");
+                    assert_matches!(cluster.load_balancing_config.dc_awareness, None);
+
+                    cass_cluster_set_load_balance_round_robin(cluster_raw);
+                    assert_matches!(cluster.load_balancing_config.rack_awareness, None);
+                }
+                /* Test invalid configurations */
+                {
+                    // Empty local rack
+                    assert_cass_error_eq!(
+                        cass_cluster_set_load_balance_rack_aware(
+                            cluster_raw,
+                            "eu\0".as_ptr() as *const i8,
+                            "\0".as_ptr() as *const i8,
                         ),
                         CassError::CASS_ERROR_LIB_BAD_PARAMS
                     );
+                }
+                {
+                    // Empty local datacenter
                     assert_cass_error_eq!(
                         cass_cluster_set_load_balance_dc_aware(
                             cluster_raw,
-                            "eu\0".as_ptr() as *const i8,
+                            "\0".as_ptr() as *const i8,
                             0,
-                            1
+                            0
+                        ),
+                        CassError::CASS_ERROR_LIB_BAD_PARAMS
+                    );
+                }
+            }
+
+            cass_cluster_free(cluster_raw);
+        }
+    }
+
+    #[test]
+    #[ntest::timeout(100)]
+    fn test_dc_failover_with_rack_awareness() {
+        let mut config = LoadBalancingConfig::default();
+        assert!(config.permits_dc_failover());
+
+        config.rack_awareness = Some(RackAwareness {
+            local_dc: "eu".to_string(),
+            local_rack: "rack1".to_string(),
+        });
+        assert!(config.permits_dc_failover());
+
+        // Combined with rack awareness, DC awareness still decides on remote datacenters
+        config.dc_awareness = Some(DcAwareness::new("eu".to_string()));
+        assert!(!config.permits_dc_failover());
+        config
+            .dc_awareness
+            .as_mut()
+            .unwrap()
+            .used_hosts_per_remote_dc = 2;
+        assert!(config.permits_dc_failover());
+    }
+
+    #[test]
+    #[ntest::timeout(100)]
+    fn test_tcp_keepalive() {
+        unsafe {
+            let cluster_raw = cass_cluster_new();
+            {
+                let config = &ptr_to_ref(cluster_raw).session_builder.config;
+                assert_eq!(config.tcp_keepalive_interval, None);
+
+                cass_cluster_set_tcp_keepalive(cluster_raw, true as cass_bool_t, 45);
+                assert_eq!(config.tcp_keepalive_interval, Some(Duration::from_secs(45)));
+
+                // A zero delay is raised to a second
+                cass_cluster_set_tcp_keepalive(cluster_raw, true as cass_bool_t, 0);
+                assert_eq!(config.tcp_keepalive_interval, Some(Duration::from_secs(1)));
+
+                // The delay is ignored when keepalive is disabled
+                cass_cluster_set_tcp_keepalive(cluster_raw, false as cass_bool_t, 45);
+                assert_eq!(config.tcp_keepalive_interval, None);
+            }
+            cass_cluster_free(cluster_raw);
+        }
+    }
+
+    #[test]
+    #[ntest::timeout(100)]
+    fn test_timestamp_gen() {
+        unsafe {
+            let cluster_raw = cass_cluster_new();
+            {
+                let cluster = ptr_to_ref(cluster_raw);
+                // Client-side timestamps by default
+                assert_matches!(
+                    cluster.timestamp_generator().as_ref(),
+                    TimestampGenerator::Monotonic(_)
+                );
+
+                let server_side = cass_timestamp_gen_server_side_new();
+                cass_cluster_set_timestamp_gen(cluster_raw, server_side);
+                // The cluster keeps the generator alive after it's freed by the user
+                cass_timestamp_gen_free(server_side);
+                let cluster = ptr_to_ref(cluster_raw);
+                assert_matches!(
+                    cluster.timestamp_generator().as_ref(),
+                    TimestampGenerator::ServerSide
+                );
+                assert_eq!(cluster.timestamp_generator().next_timestamp(), None);
+
+                let monotonic = cass_timestamp_gen_monotonic_new_with_settings(-1, 0);
+                cass_cluster_set_timestamp_gen(cluster_raw, monotonic);
+                cass_timestamp_gen_free(monotonic);
+                let cluster = ptr_to_ref(cluster_raw);
+                let first = cluster.timestamp_generator().next_timestamp().unwrap();
+                let second = cluster.timestamp_generator().next_timestamp().unwrap();
+                assert!(first < second);
+            }
+            cass_cluster_free(cluster_raw);
+        }
+    }
+
+    #[test]
+    #[ntest::timeout(100)]
+    fn test_heartbeat() {
+        unsafe {
+            let cluster_raw = cass_cluster_new();
+            {
+                let config = &ptr_to_ref(cluster_raw).session_builder.config;
+                assert_eq!(config.keepalive_interval, Some(Duration::from_secs(30)));
+                assert_eq!(config.keepalive_timeout, Some(Duration::from_secs(60)));
+
+                cass_cluster_set_connection_heartbeat_interval(cluster_raw, 5);
+                cass_cluster_set_connection_idle_timeout(cluster_raw, 15);
+                assert_eq!(config.keepalive_interval, Some(Duration::from_secs(5)));
+                assert_eq!(config.keepalive_timeout, Some(Duration::from_secs(15)));
+
+                // Zero disables heartbeats
+                cass_cluster_set_connection_heartbeat_interval(cluster_raw, 0);
+                assert_eq!(config.keepalive_interval, None);
+            }
+            cass_cluster_free(cluster_raw);
+        }
+    }
+
+    #[test]
+    #[ntest::timeout(100)]
+    fn test_pool_size() {
+        unsafe {
+            let cluster_raw = cass_cluster_new();
+            {
+                let cluster = ptr_to_ref(cluster_raw);
+                assert_matches!(cluster.pool_size, None);
+
+                assert_cass_error_eq!(
+                    cass_cluster_set_core_connections_per_host(cluster_raw, 0),
+                    CassError::CASS_ERROR_LIB_BAD_PARAMS
+                );
+                assert_cass_error_eq!(
+                    cass_cluster_set_max_connections_per_host(cluster_raw, 0),
+                    CassError::CASS_ERROR_LIB_BAD_PARAMS
+                );
+                assert_cass_error_eq!(
+                    cass_cluster_set_max_concurrent_creation(cluster_raw, 0),
+                    CassError::CASS_ERROR_LIB_BAD_PARAMS
+                );
+                assert_matches!(cluster.pool_size, None);
+
+                assert_cass_error_eq!(
+                    cass_cluster_set_core_connections_per_host(cluster_raw, 3),
+                    CassError::CASS_OK
+                );
+                assert_matches!(cluster.pool_size, Some(PoolSize::PerHost(n)) if n.get() == 3);
+
+                // Max connections per host are ignored
+                assert_cass_error_eq!(
+                    cass_cluster_set_max_connections_per_host(cluster_raw, 2),
+                    CassError::CASS_OK
+                );
+                assert_matches!(cluster.pool_size, Some(PoolSize::PerHost(n)) if n.get() == 3);
+            }
+            cass_cluster_free(cluster_raw);
+        }
+    }
+
+    #[test]
+    #[ntest::timeout(100)]
+    fn test_request_throttling() {
+        unsafe {
+            let cluster_raw = cass_cluster_new();
+            {
+                let cluster = ptr_to_ref(cluster_raw);
+                assert!(!cluster.request_throttling_config.is_needed());
+
+                assert_cass_error_eq!(
+                    cass_cluster_set_max_concurrent_requests_threshold(cluster_raw, 0),
+                    CassError::CASS_ERROR_LIB_BAD_PARAMS
+                );
+                assert_cass_error_eq!(
+                    cass_cluster_set_pending_requests_high_water_mark(cluster_raw, 0),
+                    CassError::CASS_ERROR_LIB_BAD_PARAMS
+                );
+                assert!(!cluster.request_throttling_config.is_needed());
+
+                assert_cass_error_eq!(
+                    cass_cluster_set_max_concurrent_requests_threshold(cluster_raw, 500),
+                    CassError::CASS_OK
+                );
+                assert_cass_error_eq!(
+                    cass_cluster_set_pending_requests_high_water_mark(cluster_raw, 256),
+                    CassError::CASS_OK
+                );
+                assert_cass_error_eq!(
+                    cass_cluster_set_pending_requests_low_water_mark(cluster_raw, 128),
+                    CassError::CASS_OK
+                );
+
+                // Low water mark cannot be greater than high water mark and vice versa
+                assert_cass_error_eq!(
+                    cass_cluster_set_pending_requests_low_water_mark(cluster_raw, 257),
+                    CassError::CASS_ERROR_LIB_BAD_PARAMS
+                );
+                assert_cass_error_eq!(
+                    cass_cluster_set_pending_requests_high_water_mark(cluster_raw, 127),
+                    CassError::CASS_ERROR_LIB_BAD_PARAMS
+                );
+
+                assert_eq!(
+                    cluster.request_throttling_config,
+                    RequestThrottlingConfig {
+                        max_concurrent_requests: NonZeroUsize::new(500),
+                        high_water_mark: NonZeroUsize::new(256),
+                        low_water_mark: Some(128),
+                    }
+                );
+            }
+            cass_cluster_free(cluster_raw);
+        }
+    }
+
+    #[test]
+    #[ntest::timeout(100)]
+    fn test_address_translation() {
+        unsafe extern "C" fn no_translation(
+            _address: *const CassInet,
+            _port: c_int,
+            _translated_address: *mut CassInet,
+            _translated_port: *mut c_int,
+            _data: *mut c_void,
+        ) -> cass_bool_t {
+            cass_false
+        }
+
+        unsafe {
+            let cluster_raw = cass_cluster_new();
+            {
+                let cluster = ptr_to_ref(cluster_raw);
+                let translation = || {
+                    cluster
+                        .address_translator
+                        .as_deref()
+                        .map(CassAddressTranslator::translation)
+                };
+                assert_matches!(translation(), None);
+
+                assert_cass_error_eq!(
+                    cass_cluster_set_address_translator_callback(
+                        cluster_raw,
+                        None,
+                        None,
+                        std::ptr::null_mut()
+                    ),
+                    CassError::CASS_ERROR_LIB_BAD_PARAMS
+                );
+                assert_cass_error_eq!(
+                    cass_cluster_set_address_translator_callback(
+                        cluster_raw,
+                        Some(no_translation),
+                        None,
+                        std::ptr::null_mut()
+                    ),
+                    CassError::CASS_OK
+                );
+                assert_matches!(translation(), Some(AddressTranslation::Callback { .. }));
+
+                // Invalid addresses and ports
+                for (address, port) in [("not an ip", 9042), ("10.0.0.1", -1), ("10.0.0.1", 65536)]
+                {
+                    let (c_address, c_address_len) = str_to_c_str_n(address);
+                    assert_cass_error_eq!(
+                        cass_cluster_add_address_translation_n(
+                            cluster_raw,
+                            c_address,
+                            c_address_len,
+                            port,
+                            make_c_str!("203.0.113.1"),
+                            11,
+                            9042
                         ),
                         CassError::CASS_ERROR_LIB_BAD_PARAMS
                     );
                 }
+                assert_matches!(translation(), Some(AddressTranslation::Callback { .. }));
+
+                // The static map replaces the callback
+                assert_cass_error_eq!(
+                    cass_cluster_add_address_translation(
+                        cluster_raw,
+                        make_c_str!("10.0.0.1"),
+                        9042,
+                        make_c_str!("203.0.113.1"),
+                        19042
+                    ),
+                    CassError::CASS_OK
+                );
+                assert_cass_error_eq!(
+                    cass_cluster_add_address_translation(
+                        cluster_raw,
+                        make_c_str!("fd00::2"),
+                        9042,
+                        make_c_str!("2001:db8::2"),
+                        9042
+                    ),
+                    CassError::CASS_OK
+                );
+                let addr = |s: &str| s.parse::<SocketAddr>().unwrap();
+                assert_matches!(
+                    translation(),
+                    Some(AddressTranslation::Map(translations))
+                        if *translations == HashMap::from([
+                            (addr("10.0.0.1:9042"), addr("203.0.113.1:19042")),
+                            (addr("[fd00::2]:9042"), addr("[2001:db8::2]:9042")),
+                        ])
+                );
+            }
+            cass_cluster_free(cluster_raw);
+        }
+    }
+
+    #[test]
+    #[ntest::timeout(100)]
+    fn test_host_filtering() {
+        unsafe {
+            let cluster_raw = cass_cluster_new();
+            let exec_profile_raw = cass_execution_profile_new();
+            {
+                let cluster = ptr_to_ref(cluster_raw);
+                let filtering = &cluster.load_balancing_config.filtering;
+                let host1: IpAddr = "127.0.0.1".parse().unwrap();
+                let host2: IpAddr = "::1".parse().unwrap();
+
+                assert!(!filtering.is_enabled());
+
+                // First call sets, subsequent calls append
+                cass_cluster_set_whitelist_filtering(cluster_raw, make_c_str!("127.0.0.1"));
+                cass_cluster_set_whitelist_filtering(cluster_raw, make_c_str!(" ::1 ,"));
+                assert_eq!(filtering.whitelist_hosts, vec![host1, host2]);
+
+                // Empty string clears the list
+                cass_cluster_set_whitelist_filtering(cluster_raw, make_c_str!(""));
+                assert!(filtering.whitelist_hosts.is_empty());
+
+                cass_cluster_set_blacklist_filtering(cluster_raw, make_c_str!("127.0.0.1"));
+                assert_eq!(filtering.blacklist_hosts, vec![host1]);
+                assert!(filtering.is_enabled());
+
+                cass_cluster_set_whitelist_dc_filtering(cluster_raw, make_c_str!("dc1, dc2"));
+                cass_cluster_set_blacklist_dc_filtering(cluster_raw, make_c_str!("dc3"));
+                assert_eq!(filtering.whitelist_dcs, vec!["dc1", "dc2"]);
+                assert_eq!(filtering.blacklist_dcs, vec!["dc3"]);
+                cass_cluster_set_whitelist_dc_filtering(cluster_raw, make_c_str!(""));
+                assert!(filtering.whitelist_dcs.is_empty());
+
+                let profile = ptr_to_ref(exec_profile_raw);
+                let filtering = &profile.load_balancing_config().filtering;
+                assert_cass_error_eq!(
+                    cass_execution_profile_set_whitelist_filtering(
+                        exec_profile_raw,
+                        make_c_str!("127.0.0.1,::1")
+                    ),
+                    CassError::CASS_OK
+                );
+                assert_eq!(filtering.whitelist_hosts, vec![host1, host2]);
+                assert_cass_error_eq!(
+                    cass_execution_profile_set_blacklist_filtering(
+                        exec_profile_raw,
+                        make_c_str!("not_an_address")
+                    ),
+                    CassError::CASS_ERROR_LIB_BAD_PARAMS
+                );
+                assert!(filtering.blacklist_hosts.is_empty());
+
+                assert_cass_error_eq!(
+                    cass_execution_profile_set_whitelist_dc_filtering(
+                        exec_profile_raw,
+                        make_c_str!("dc1")
+                    ),
+                    CassError::CASS_OK
+                );
+                assert_cass_error_eq!(
+                    cass_execution_profile_set_blacklist_dc_filtering(
+                        exec_profile_raw,
+                        make_c_str!("dc2,dc3")
+                    ),
+                    CassError::CASS_OK
+                );
+                assert_eq!(filtering.whitelist_dcs, vec!["dc1"]);
+                assert_eq!(filtering.blacklist_dcs, vec!["dc2", "dc3"]);
             }
 
+            cass_execution_profile_free(exec_profile_raw);
             cass_cluster_free(cluster_raw);
         }
     }
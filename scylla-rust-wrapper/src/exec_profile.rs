@@ -1,5 +1,5 @@
 use std::convert::{TryFrom, TryInto};
-use std::ffi::c_char;
+use std::ffi::{c_char, c_void};
 use std::future::Future;
 use std::ops::Deref;
 use std::sync::{Arc, RwLock};
@@ -17,7 +17,11 @@ use crate::argconv::{free_boxed, ptr_to_cstr_n, ptr_to_ref, ptr_to_ref_mut, strl
 use crate::batch::CassBatch;
 use crate::cass_error::CassError;
 use crate::cass_types::CassConsistency;
-use crate::cluster::{set_load_balance_dc_aware_n, LoadBalancingConfig};
+use crate::cluster::{
+    set_dc_filtering_n, set_host_filtering_n, set_load_balance_custom, set_load_balance_dc_aware_n,
+    set_load_balance_rack_aware_n, LoadBalancingConfig,
+};
+use crate::load_balancing::CassLoadBalancingCallback;
 use crate::retry_policy::CassRetryPolicy;
 use crate::retry_policy::RetryPolicy::{
     DefaultRetryPolicy, DowngradingConsistencyRetryPolicy, FallthroughRetryPolicy,
@@ -26,6 +30,7 @@ use crate::session::CassSessionInner;
 use crate::statement::CassStatement;
 use crate::types::{
     cass_bool_t, cass_double_t, cass_int32_t, cass_int64_t, cass_uint32_t, cass_uint64_t, size_t,
+    CassDataCleanupCallback,
 };
 
 #[derive(Clone, Debug)]
@@ -42,6 +47,10 @@ impl CassExecProfile {
         }
     }
 
+    pub(crate) fn load_balancing_config(&self) -> &LoadBalancingConfig {
+        &self.load_balancing_config
+    }
+
     pub(crate) async fn build(self) -> ExecutionProfile {
         self.inner
             .load_balancing_policy(self.load_balancing_config.build().await)
@@ -337,12 +346,168 @@ pub unsafe extern "C" fn cass_execution_profile_set_load_balance_dc_aware_n(
     )
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn cass_execution_profile_set_load_balance_rack_aware(
+    profile: *mut CassExecProfile,
+    local_dc: *const c_char,
+    local_rack: *const c_char,
+) -> CassError {
+    cass_execution_profile_set_load_balance_rack_aware_n(
+        profile,
+        local_dc,
+        strlen(local_dc),
+        local_rack,
+        strlen(local_rack),
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_execution_profile_set_load_balance_rack_aware_n(
+    profile: *mut CassExecProfile,
+    local_dc: *const c_char,
+    local_dc_length: size_t,
+    local_rack: *const c_char,
+    local_rack_length: size_t,
+) -> CassError {
+    let profile_builder = ptr_to_ref_mut(profile);
+
+    set_load_balance_rack_aware_n(
+        &mut profile_builder.load_balancing_config,
+        local_dc,
+        local_dc_length,
+        local_rack,
+        local_rack_length,
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_execution_profile_set_load_balance_custom(
+    profile: *mut CassExecProfile,
+    callback: CassLoadBalancingCallback,
+    cleanup_callback: CassDataCleanupCallback,
+    data: *mut c_void,
+) -> CassError {
+    let profile_builder = ptr_to_ref_mut(profile);
+
+    set_load_balance_custom(
+        &mut profile_builder.load_balancing_config,
+        callback,
+        cleanup_callback,
+        data,
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_execution_profile_set_whitelist_filtering(
+    profile: *mut CassExecProfile,
+    hosts: *const c_char,
+) -> CassError {
+    cass_execution_profile_set_whitelist_filtering_n(profile, hosts, strlen(hosts))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_execution_profile_set_whitelist_filtering_n(
+    profile: *mut CassExecProfile,
+    hosts: *const c_char,
+    hosts_length: size_t,
+) -> CassError {
+    let profile_builder = ptr_to_ref_mut(profile);
+
+    set_host_filtering_n(
+        &mut profile_builder
+            .load_balancing_config
+            .filtering
+            .whitelist_hosts,
+        hosts,
+        hosts_length,
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_execution_profile_set_blacklist_filtering(
+    profile: *mut CassExecProfile,
+    hosts: *const c_char,
+) -> CassError {
+    cass_execution_profile_set_blacklist_filtering_n(profile, hosts, strlen(hosts))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_execution_profile_set_blacklist_filtering_n(
+    profile: *mut CassExecProfile,
+    hosts: *const c_char,
+    hosts_length: size_t,
+) -> CassError {
+    let profile_builder = ptr_to_ref_mut(profile);
+
+    set_host_filtering_n(
+        &mut profile_builder
+            .load_balancing_config
+            .filtering
+            .blacklist_hosts,
+        hosts,
+        hosts_length,
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_execution_profile_set_whitelist_dc_filtering(
+    profile: *mut CassExecProfile,
+    dcs: *const c_char,
+) -> CassError {
+    cass_execution_profile_set_whitelist_dc_filtering_n(profile, dcs, strlen(dcs))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_execution_profile_set_whitelist_dc_filtering_n(
+    profile: *mut CassExecProfile,
+    dcs: *const c_char,
+    dcs_length: size_t,
+) -> CassError {
+    let profile_builder = ptr_to_ref_mut(profile);
+
+    set_dc_filtering_n(
+        &mut profile_builder
+            .load_balancing_config
+            .filtering
+            .whitelist_dcs,
+        dcs,
+        dcs_length,
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_execution_profile_set_blacklist_dc_filtering(
+    profile: *mut CassExecProfile,
+    dcs: *const c_char,
+) -> CassError {
+    cass_execution_profile_set_blacklist_dc_filtering_n(profile, dcs, strlen(dcs))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_execution_profile_set_blacklist_dc_filtering_n(
+    profile: *mut CassExecProfile,
+    dcs: *const c_char,
+    dcs_length: size_t,
+) -> CassError {
+    let profile_builder = ptr_to_ref_mut(profile);
+
+    set_dc_filtering_n(
+        &mut profile_builder
+            .load_balancing_config
+            .filtering
+            .blacklist_dcs,
+        dcs,
+        dcs_length,
+    )
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn cass_execution_profile_set_load_balance_round_robin(
     profile: *mut CassExecProfile,
 ) -> CassError {
     let profile_builder = ptr_to_ref_mut(profile);
     profile_builder.load_balancing_config.dc_awareness = None;
+    profile_builder.load_balancing_config.rack_awareness = None;
 
     CassError::CASS_OK
 }
@@ -410,6 +575,19 @@ pub unsafe extern "C" fn cass_execution_profile_set_token_aware_routing(
     CassError::CASS_OK
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn cass_execution_profile_set_token_aware_routing_shuffle_replicas(
+    profile: *mut CassExecProfile,
+    enabled: cass_bool_t,
+) -> CassError {
+    let profile_builder = ptr_to_ref_mut(profile);
+    profile_builder
+        .load_balancing_config
+        .token_aware_shuffling_enabled = enabled != 0;
+
+    CassError::CASS_OK
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -451,10 +629,12 @@ mod tests {
                 {
                     assert_matches!(profile.load_balancing_config.dc_awareness, None);
                     assert!(profile.load_balancing_config.token_awareness_enabled);
+                    assert!(profile.load_balancing_config.token_aware_shuffling_enabled);
                     assert!(!profile.load_balancing_config.latency_awareness_enabled);
                 }
                 {
                     cass_execution_profile_set_token_aware_routing(profile_raw, 0);
+                    cass_execution_profile_set_token_aware_routing_shuffle_replicas(profile_raw, 0);
                     assert_cass_error_eq!(
                         cass_execution_profile_set_load_balance_dc_aware(
                             profile_raw,
@@ -479,11 +659,11 @@ mod tests {
                     let dc_awareness = profile.load_balancing_config.dc_awareness.as_ref().unwrap();
                     assert_eq!(dc_awareness.local_dc, "eu");
                     assert!(!profile.load_balancing_config.token_awareness_enabled);
+                    assert!(!profile.load_balancing_config.token_aware_shuffling_enabled);
                     assert!(profile.load_balancing_config.latency_awareness_enabled);
                 }
-                /* Test invalid configurations */
                 {
-                    // Nonzero deprecated parameters
+                    // Remote datacenters settings
                     assert_cass_error_eq!(
                         cass_execution_profile_set_load_balance_dc_aware(
                             profile_raw,
@@ -491,14 +671,22 @@ mod tests {
                             1,
                             0
                         ),
-                        CassError::CASS_ERROR_LIB_BAD_PARAMS
+                        CassError::CASS_OK
                     );
+
+                    let dc_awareness = profile.load_balancing_config.dc_awareness.as_ref().unwrap();
+                    assert_eq!(dc_awareness.used_hosts_per_remote_dc, 1);
+                    assert!(!dc_awareness.allow_remote_dcs_for_local_cl);
+                }
+                /* Test invalid configurations */
+                {
+                    // Null local datacenter
                     assert_cass_error_eq!(
                         cass_execution_profile_set_load_balance_dc_aware(
                             profile_raw,
-                            "eu\0".as_ptr() as *const i8,
+                            std::ptr::null(),
                             0,
-                            1
+                            0
                         ),
                         CassError::CASS_ERROR_LIB_BAD_PARAMS
                     );
@@ -94,6 +94,10 @@ impl CassFuture {
         cass_fut
     }
 
+    pub fn make_ready_raw(r: CassFutureResult) -> *const CassFuture {
+        Self::new_ready(r).into_raw()
+    }
+
     pub fn new_ready(r: CassFutureResult) -> Arc<Self> {
         Arc::new(CassFuture {
             state: Mutex::new(CassFutureState {
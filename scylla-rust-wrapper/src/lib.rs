@@ -8,27 +8,35 @@ use tokio::runtime::Runtime;
 
 #[macro_use]
 mod binding;
+mod address_translation;
 mod argconv;
+pub mod authentication;
 pub mod batch;
 pub mod cass_error;
 pub mod cass_types;
+mod cloud;
 pub mod cluster;
 pub mod collection;
 pub mod exec_profile;
 mod external;
 pub mod future;
 pub mod inet;
+pub mod load_balancing;
 mod logging;
 pub mod metadata;
 pub mod prepared;
 pub mod query_error;
 pub mod query_result;
+mod request_throttler;
+mod resolver;
 pub mod retry_policy;
 pub mod session;
 pub mod ssl;
 pub mod statement;
 #[cfg(test)]
 pub mod testing;
+pub mod timestamp_generator;
+mod tls_tunnel;
 pub mod tuple;
 pub mod types;
 pub mod user_type;
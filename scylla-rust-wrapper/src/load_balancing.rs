@@ -0,0 +1,964 @@
+use crate::argconv::*;
+use crate::cass_error::CassError;
+use crate::cass_types::CassConsistency;
+use crate::cluster::{DcAwareness, RackAwareness};
+use crate::inet::CassInet;
+use crate::tls_tunnel::node_address;
+use crate::types::*;
+use scylla::host_filter::HostFilter;
+use scylla::load_balancing::{FallbackPlan, LoadBalancingPolicy, NodeRef, RoutingInfo};
+use scylla::statement::{Consistency, SerialConsistency};
+use scylla::transport::errors::QueryError;
+use scylla::transport::topology::Peer;
+use scylla::transport::{ClusterData, Node};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::os::raw::{c_char, c_void};
+use std::sync::Arc;
+use std::time::Duration;
+
+// Metadata of the hosts handed out to `CassLoadBalancingCallback`.
+pub type CassHostMetadata = Node;
+
+// Routing information of the request handed out to `CassLoadBalancingCallback`.
+// It is only valid for the duration of the callback.
+pub struct CassRoutingInfo {
+    keyspace: Option<String>,
+    token: Option<i64>,
+    consistency: Consistency,
+}
+
+impl From<&RoutingInfo<'_>> for CassRoutingInfo {
+    fn from(routing_info: &RoutingInfo<'_>) -> Self {
+        Self {
+            keyspace: routing_info.keyspace.map(str::to_string),
+            token: routing_info.token.map(|token| token.value),
+            consistency: routing_info.consistency,
+        }
+    }
+}
+
+type LoadBalancingCallback = unsafe extern "C" fn(
+    routing_info: *const CassRoutingInfo,
+    hosts: *const *const CassHostMetadata,
+    hosts_count: size_t,
+    plan: *mut size_t,
+    data: *mut c_void,
+) -> size_t;
+
+pub type CassLoadBalancingCallback = Option<LoadBalancingCallback>;
+
+// Hosts and datacenters allowed and denied by
+// `cass_{cluster,execution_profile}_set_{white,black}list[_dc]_filtering()`.
+// Empty lists disable the respective filtering.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct FilteringConfig {
+    pub(crate) whitelist_hosts: Vec<IpAddr>,
+    pub(crate) blacklist_hosts: Vec<IpAddr>,
+    pub(crate) whitelist_dcs: Vec<String>,
+    pub(crate) blacklist_dcs: Vec<String>,
+}
+
+impl FilteringConfig {
+    pub(crate) fn is_enabled(&self) -> bool {
+        !self.whitelist_hosts.is_empty()
+            || !self.blacklist_hosts.is_empty()
+            || !self.whitelist_dcs.is_empty()
+            || !self.blacklist_dcs.is_empty()
+    }
+
+    // Nodes with unknown datacenter are rejected by the datacenter whitelist,
+    // but not by the blacklist.
+    pub(crate) fn accepts(&self, address: IpAddr, datacenter: Option<&str>) -> bool {
+        let dc_listed = |dcs: &[String]| datacenter.map_or(false, |dc| dcs.iter().any(|d| d == dc));
+
+        (self.whitelist_hosts.is_empty() || self.whitelist_hosts.contains(&address))
+            && !self.blacklist_hosts.contains(&address)
+            && (self.whitelist_dcs.is_empty() || dc_listed(&self.whitelist_dcs))
+            && !dc_listed(&self.blacklist_dcs)
+    }
+
+    fn accepts_node(&self, node: NodeRef<'_>) -> bool {
+        self.accepts(node_address(node.address).ip(), node.datacenter.as_deref())
+    }
+}
+
+// Removes the nodes rejected by the filtering config from the plans of the wrapped policy.
+#[derive(Debug)]
+pub(crate) struct FilteringPolicy {
+    inner: Arc<dyn LoadBalancingPolicy>,
+    filtering: FilteringConfig,
+}
+
+impl FilteringPolicy {
+    pub(crate) fn new(inner: Arc<dyn LoadBalancingPolicy>, filtering: FilteringConfig) -> Self {
+        Self { inner, filtering }
+    }
+}
+
+impl LoadBalancingPolicy for FilteringPolicy {
+    fn pick<'a>(&'a self, query: &'a RoutingInfo, cluster: &'a ClusterData) -> Option<NodeRef<'a>> {
+        match self.inner.pick(query, cluster) {
+            Some(node) if self.filtering.accepts_node(node) => Some(node),
+            // The picked node is filtered out, so the next best one is taken.
+            _ => self.fallback(query, cluster).next(),
+        }
+    }
+
+    fn fallback<'a>(
+        &'a self,
+        query: &'a RoutingInfo,
+        cluster: &'a ClusterData,
+    ) -> FallbackPlan<'a> {
+        Box::new(
+            self.inner
+                .fallback(query, cluster)
+                .filter(move |node| self.filtering.accepts_node(node)),
+        )
+    }
+
+    fn on_query_success(&self, query: &RoutingInfo, latency: Duration, node: NodeRef<'_>) {
+        self.inner.on_query_success(query, latency, node)
+    }
+
+    fn on_query_failure(
+        &self,
+        query: &RoutingInfo,
+        latency: Duration,
+        node: NodeRef<'_>,
+        error: &QueryError,
+    ) {
+        self.inner.on_query_failure(query, latency, node, error)
+    }
+
+    fn name(&self) -> String {
+        format!("FilteringPolicy({})", self.inner.name())
+    }
+}
+
+// Restricts the remote datacenter part of the plans of the wrapped policy
+// to `used_hosts_per_remote_dc` nodes of every remote datacenter. Queries with
+// LOCAL_ONE, LOCAL_QUORUM or LOCAL_SERIAL consistency are kept in the local
+// datacenter unless `allow_remote_dcs_for_local_cl` is set.
+#[derive(Debug)]
+pub(crate) struct RemoteDcPolicy {
+    inner: Arc<dyn LoadBalancingPolicy>,
+    dc_awareness: DcAwareness,
+}
+
+impl RemoteDcPolicy {
+    pub(crate) fn new(inner: Arc<dyn LoadBalancingPolicy>, dc_awareness: DcAwareness) -> Self {
+        Self {
+            inner,
+            dc_awareness,
+        }
+    }
+
+    fn is_local(&self, node: NodeRef<'_>) -> bool {
+        node.datacenter.as_deref() == Some(self.dc_awareness.local_dc.as_str())
+    }
+}
+
+fn remote_dcs_permitted(
+    dc_awareness: &DcAwareness,
+    consistency: Consistency,
+    serial_consistency: Option<SerialConsistency>,
+) -> bool {
+    let is_dc_local = matches!(
+        consistency,
+        Consistency::LocalOne | Consistency::LocalQuorum
+    ) || serial_consistency == Some(SerialConsistency::LocalSerial);
+
+    dc_awareness.used_hosts_per_remote_dc > 0
+        && (!is_dc_local || dc_awareness.allow_remote_dcs_for_local_cl)
+}
+
+// Counts the hosts taken from every remote datacenter of a single plan.
+fn take_remote_host<'a>(
+    dc_awareness: &DcAwareness,
+    used_hosts: &mut HashMap<Option<&'a str>, u32>,
+    datacenter: Option<&'a str>,
+) -> bool {
+    let used = used_hosts.entry(datacenter).or_insert(0);
+    if *used < dc_awareness.used_hosts_per_remote_dc {
+        *used += 1;
+        true
+    } else {
+        false
+    }
+}
+
+impl LoadBalancingPolicy for RemoteDcPolicy {
+    fn pick<'a>(&'a self, query: &'a RoutingInfo, cluster: &'a ClusterData) -> Option<NodeRef<'a>> {
+        match self.inner.pick(query, cluster) {
+            Some(node) if self.is_local(node) => Some(node),
+            // A remote node was picked, so it has to pass the restrictions of the fallback plan.
+            _ => self.fallback(query, cluster).next(),
+        }
+    }
+
+    fn fallback<'a>(
+        &'a self,
+        query: &'a RoutingInfo,
+        cluster: &'a ClusterData,
+    ) -> FallbackPlan<'a> {
+        let remote_dcs_permitted = remote_dcs_permitted(
+            &self.dc_awareness,
+            query.consistency,
+            query.serial_consistency,
+        );
+        let mut used_hosts = HashMap::new();
+
+        Box::new(self.inner.fallback(query, cluster).filter(move |node| {
+            self.is_local(node)
+                || (remote_dcs_permitted
+                    && take_remote_host(
+                        &self.dc_awareness,
+                        &mut used_hosts,
+                        node.datacenter.as_deref(),
+                    ))
+        }))
+    }
+
+    fn on_query_success(&self, query: &RoutingInfo, latency: Duration, node: NodeRef<'_>) {
+        self.inner.on_query_success(query, latency, node)
+    }
+
+    fn on_query_failure(
+        &self,
+        query: &RoutingInfo,
+        latency: Duration,
+        node: NodeRef<'_>,
+        error: &QueryError,
+    ) {
+        self.inner.on_query_failure(query, latency, node, error)
+    }
+
+    fn name(&self) -> String {
+        format!("RemoteDcPolicy({})", self.inner.name())
+    }
+}
+
+// Orders the plans of the wrapped policy: replicas before other nodes and
+// the local rack before the rest of the local datacenter, which in turn
+// goes before remote datacenters. The order of the wrapped policy
+// (e.g. the one imposed by latency awareness) is kept within every group.
+#[derive(Debug)]
+pub(crate) struct RackAwarePolicy {
+    inner: Arc<dyn LoadBalancingPolicy>,
+    rack_awareness: RackAwareness,
+    token_awareness_enabled: bool,
+}
+
+impl RackAwarePolicy {
+    pub(crate) fn new(
+        inner: Arc<dyn LoadBalancingPolicy>,
+        rack_awareness: RackAwareness,
+        token_awareness_enabled: bool,
+    ) -> Self {
+        Self {
+            inner,
+            rack_awareness,
+            token_awareness_enabled,
+        }
+    }
+
+    fn replicas(&self, query: &RoutingInfo, cluster: &ClusterData) -> Vec<Arc<Node>> {
+        match (self.token_awareness_enabled, query.token, query.keyspace) {
+            (true, Some(token), Some(keyspace)) => cluster.get_token_endpoints(keyspace, token),
+            _ => Vec::new(),
+        }
+    }
+}
+
+// Rank of the replicas in the local rack.
+const BEST_RACK_AWARE_RANK: (bool, bool, bool) = (false, false, false);
+
+// Smaller ranks come first in query plans. Only replicas
+// from the local datacenter take precedence over other nodes.
+fn rack_aware_rank(
+    rack_awareness: &RackAwareness,
+    datacenter: Option<&str>,
+    rack: Option<&str>,
+    is_replica: bool,
+) -> (bool, bool, bool) {
+    let is_local_dc = datacenter == Some(rack_awareness.local_dc.as_str());
+    let is_local_rack = is_local_dc && rack == Some(rack_awareness.local_rack.as_str());
+
+    (!is_local_dc, !is_replica, !is_local_rack)
+}
+
+// Yields the nodes of a plan in the order of their ranks, keeping the order of the plan
+// among nodes of the same rank. Nodes of the best rank are yielded as soon as they are
+// reached, the others are held back until the plan runs out.
+struct PreferredFirst<T, K, I, R> {
+    plan: I,
+    rank: R,
+    best_rank: K,
+    held_back: BTreeMap<K, VecDeque<T>>,
+}
+
+impl<T, K, I, R> PreferredFirst<T, K, I, R> {
+    fn new(plan: I, rank: R, best_rank: K) -> Self {
+        Self {
+            plan,
+            rank,
+            best_rank,
+            held_back: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T, K, I, R> Iterator for PreferredFirst<T, K, I, R>
+where
+    K: Copy + Ord,
+    I: Iterator<Item = T>,
+    R: Fn(&T) -> K,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        for node in &mut self.plan {
+            let rank = (self.rank)(&node);
+            if rank == self.best_rank {
+                return Some(node);
+            }
+            self.held_back.entry(rank).or_default().push_back(node);
+        }
+
+        let rank = *self.held_back.keys().next()?;
+        let nodes = self.held_back.get_mut(&rank).unwrap();
+        let node = nodes.pop_front();
+        if nodes.is_empty() {
+            self.held_back.remove(&rank);
+        }
+        node
+    }
+}
+
+impl LoadBalancingPolicy for RackAwarePolicy {
+    fn pick<'a>(&'a self, query: &'a RoutingInfo, cluster: &'a ClusterData) -> Option<NodeRef<'a>> {
+        self.fallback(query, cluster).next()
+    }
+
+    fn fallback<'a>(
+        &'a self,
+        query: &'a RoutingInfo,
+        cluster: &'a ClusterData,
+    ) -> FallbackPlan<'a> {
+        let replicas = self.replicas(query, cluster);
+        let rack_awareness = &self.rack_awareness;
+
+        Box::new(PreferredFirst::new(
+            self.inner.fallback(query, cluster),
+            move |node: &NodeRef<'a>| {
+                rack_aware_rank(
+                    rack_awareness,
+                    node.datacenter.as_deref(),
+                    node.rack.as_deref(),
+                    replicas.iter().any(|replica| Arc::ptr_eq(replica, node)),
+                )
+            },
+            BEST_RACK_AWARE_RANK,
+        ))
+    }
+
+    fn on_query_success(&self, query: &RoutingInfo, latency: Duration, node: NodeRef<'_>) {
+        self.inner.on_query_success(query, latency, node)
+    }
+
+    fn on_query_failure(
+        &self,
+        query: &RoutingInfo,
+        latency: Duration,
+        node: NodeRef<'_>,
+        error: &QueryError,
+    ) {
+        self.inner.on_query_failure(query, latency, node, error)
+    }
+
+    fn name(&self) -> String {
+        format!("RackAwarePolicy({})", self.inner.name())
+    }
+}
+
+// Callback registered with `cass_{cluster,execution_profile}_set_load_balance_custom()`.
+pub(crate) struct CustomPolicyCallback {
+    callback: LoadBalancingCallback,
+    data_cleanup_callback: CassDataCleanupCallback,
+    data: *mut c_void,
+}
+
+// The user data is only ever accessed by the user-provided callback, which is
+// required to be thread-safe, as it is called concurrently for every request.
+unsafe impl Send for CustomPolicyCallback {}
+unsafe impl Sync for CustomPolicyCallback {}
+
+impl CustomPolicyCallback {
+    pub(crate) fn new(
+        callback: LoadBalancingCallback,
+        data_cleanup_callback: CassDataCleanupCallback,
+        data: *mut c_void,
+    ) -> Self {
+        Self {
+            callback,
+            data_cleanup_callback,
+            data,
+        }
+    }
+}
+
+impl Drop for CustomPolicyCallback {
+    fn drop(&mut self) {
+        if let Some(cleanup) = self.data_cleanup_callback {
+            unsafe { cleanup(self.data) }
+        }
+    }
+}
+
+impl fmt::Debug for CustomPolicyCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomPolicyCallback").finish()
+    }
+}
+
+// Asks the user callback for the plan and falls back to the wrapped policy
+// if the callback returns no hosts.
+#[derive(Debug)]
+pub(crate) struct CustomPolicy {
+    inner: Arc<dyn LoadBalancingPolicy>,
+    callback: Arc<CustomPolicyCallback>,
+}
+
+impl CustomPolicy {
+    pub(crate) fn new(
+        inner: Arc<dyn LoadBalancingPolicy>,
+        callback: Arc<CustomPolicyCallback>,
+    ) -> Self {
+        Self { inner, callback }
+    }
+
+    // Indices of the planned hosts in the cluster data.
+    fn custom_plan(&self, query: &RoutingInfo, nodes: &[Arc<Node>]) -> Vec<usize> {
+        let routing_info = CassRoutingInfo::from(query);
+        let hosts: Vec<*const CassHostMetadata> = nodes.iter().map(Arc::as_ptr).collect();
+        let mut plan: Vec<size_t> = vec![0; hosts.len()];
+
+        let plan_length = unsafe {
+            (self.callback.callback)(
+                &routing_info,
+                hosts.as_ptr(),
+                hosts.len() as size_t,
+                plan.as_mut_ptr(),
+                self.callback.data,
+            )
+        };
+        plan.truncate(plan_length as usize);
+
+        select_hosts(hosts.len(), &plan)
+    }
+}
+
+// Validates the indices returned by the user callback,
+// skipping the ones which are out of range or repeated.
+fn select_hosts(hosts_count: usize, indices: &[size_t]) -> Vec<usize> {
+    let mut selected = vec![false; hosts_count];
+
+    indices
+        .iter()
+        .filter_map(|&index| {
+            let index = index as usize;
+            match selected.get_mut(index) {
+                Some(selected) if !*selected => {
+                    *selected = true;
+                    Some(index)
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+impl LoadBalancingPolicy for CustomPolicy {
+    // The callback is asked for the whole plan once, whose first host is the picked one.
+    fn pick<'a>(&'a self, query: &'a RoutingInfo, cluster: &'a ClusterData) -> Option<NodeRef<'a>> {
+        self.fallback(query, cluster).next()
+    }
+
+    fn fallback<'a>(
+        &'a self,
+        query: &'a RoutingInfo,
+        cluster: &'a ClusterData,
+    ) -> FallbackPlan<'a> {
+        let nodes = cluster.get_nodes_info();
+        let plan = self.custom_plan(query, nodes);
+
+        if plan.is_empty() {
+            self.inner.fallback(query, cluster)
+        } else {
+            Box::new(plan.into_iter().map(move |index| &nodes[index]))
+        }
+    }
+
+    fn on_query_success(&self, query: &RoutingInfo, latency: Duration, node: NodeRef<'_>) {
+        self.inner.on_query_success(query, latency, node)
+    }
+
+    fn on_query_failure(
+        &self,
+        query: &RoutingInfo,
+        latency: Duration,
+        node: NodeRef<'_>,
+        error: &QueryError,
+    ) {
+        self.inner.on_query_failure(query, latency, node, error)
+    }
+
+    fn name(&self) -> String {
+        format!("CustomPolicy({})", self.inner.name())
+    }
+}
+
+// Sends requests only to the node set with `cass_statement_set_host()`.
+#[derive(Debug)]
+pub(crate) struct TargetHostPolicy {
+    address: SocketAddr,
+}
+
+impl TargetHostPolicy {
+    pub(crate) fn new(address: SocketAddr) -> Self {
+        Self { address }
+    }
+}
+
+impl LoadBalancingPolicy for TargetHostPolicy {
+    fn pick<'a>(
+        &'a self,
+        _query: &'a RoutingInfo,
+        cluster: &'a ClusterData,
+    ) -> Option<NodeRef<'a>> {
+        cluster
+            .get_nodes_info()
+            .iter()
+            .find(|node| node_address(node.address) == self.address)
+    }
+
+    fn fallback<'a>(
+        &'a self,
+        query: &'a RoutingInfo,
+        cluster: &'a ClusterData,
+    ) -> FallbackPlan<'a> {
+        Box::new(self.pick(query, cluster).into_iter())
+    }
+
+    fn name(&self) -> String {
+        "TargetHostPolicy".to_string()
+    }
+}
+
+// Decides which nodes the driver opens connection pools to.
+pub(crate) struct CassHostFilter {
+    // A node is connected to if it is accepted by the filtering
+    // of any execution profile, including the default one.
+    profile_filterings: Vec<FilteringConfig>,
+}
+
+impl CassHostFilter {
+    pub(crate) fn new(profile_filterings: Vec<FilteringConfig>) -> Self {
+        Self { profile_filterings }
+    }
+
+    pub(crate) fn is_needed(&self) -> bool {
+        self.profile_filterings
+            .iter()
+            .all(FilteringConfig::is_enabled)
+    }
+}
+
+impl HostFilter for CassHostFilter {
+    fn accept(&self, peer: &Peer) -> bool {
+        let address = node_address(peer.address).ip();
+        self.profile_filterings
+            .iter()
+            .any(|filtering| filtering.accepts(address, peer.datacenter.as_deref()))
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_host_metadata_address(
+    host: *const CassHostMetadata,
+    address: *mut CassInet,
+) {
+    let host = ptr_to_ref(host);
+    *address = node_address(host.address).ip().into();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_host_metadata_port(host: *const CassHostMetadata) -> cass_uint16_t {
+    let host = ptr_to_ref(host);
+    node_address(host.address).port()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_host_metadata_datacenter(
+    host: *const CassHostMetadata,
+    datacenter: *mut *const c_char,
+    datacenter_length: *mut size_t,
+) -> CassError {
+    let host = ptr_to_ref(host);
+    match host.datacenter.as_deref() {
+        Some(dc) => {
+            write_str_to_c(dc, datacenter, datacenter_length);
+            CassError::CASS_OK
+        }
+        None => CassError::CASS_ERROR_LIB_NULL_VALUE,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_host_metadata_rack(
+    host: *const CassHostMetadata,
+    rack: *mut *const c_char,
+    rack_length: *mut size_t,
+) -> CassError {
+    let host = ptr_to_ref(host);
+    match host.rack.as_deref() {
+        Some(r) => {
+            write_str_to_c(r, rack, rack_length);
+            CassError::CASS_OK
+        }
+        None => CassError::CASS_ERROR_LIB_NULL_VALUE,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_host_metadata_is_up(host: *const CassHostMetadata) -> cass_bool_t {
+    let host = ptr_to_ref(host);
+    !host.is_down() as cass_bool_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_routing_info_keyspace(
+    routing_info: *const CassRoutingInfo,
+    keyspace: *mut *const c_char,
+    keyspace_length: *mut size_t,
+) -> CassError {
+    let routing_info = ptr_to_ref(routing_info);
+    match routing_info.keyspace.as_deref() {
+        Some(ks) => {
+            write_str_to_c(ks, keyspace, keyspace_length);
+            CassError::CASS_OK
+        }
+        None => CassError::CASS_ERROR_LIB_NULL_VALUE,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_routing_info_token(
+    routing_info: *const CassRoutingInfo,
+    token: *mut cass_int64_t,
+) -> CassError {
+    let routing_info = ptr_to_ref(routing_info);
+    match routing_info.token {
+        Some(t) => {
+            *token = t;
+            CassError::CASS_OK
+        }
+        None => CassError::CASS_ERROR_LIB_NULL_VALUE,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_routing_info_consistency(
+    routing_info: *const CassRoutingInfo,
+) -> CassConsistency {
+    let routing_info = ptr_to_ref(routing_info);
+    routing_info.consistency.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cluster::{set_load_balance_custom, LoadBalancingConfig};
+    use crate::testing::assert_cass_error_eq;
+    use scylla::routing::Token;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn filtering_config_accepts() {
+        let host1: IpAddr = "127.0.0.1".parse().unwrap();
+        let host2: IpAddr = "127.0.0.2".parse().unwrap();
+        let host3: IpAddr = "127.0.0.3".parse().unwrap();
+
+        let mut config = FilteringConfig::default();
+        assert!(!config.is_enabled());
+        assert!(config.accepts(host1, None));
+
+        config.whitelist_hosts = vec![host1, host2];
+        assert!(config.is_enabled());
+        assert!(config.accepts(host1, None));
+        assert!(config.accepts(host2, None));
+        assert!(!config.accepts(host3, None));
+
+        // Blacklist takes precedence over whitelist
+        config.blacklist_hosts = vec![host2];
+        assert!(config.accepts(host1, None));
+        assert!(!config.accepts(host2, None));
+        assert!(!config.accepts(host3, None));
+
+        config.whitelist_hosts.clear();
+        assert!(config.accepts(host1, None));
+        assert!(!config.accepts(host2, None));
+        assert!(config.accepts(host3, None));
+    }
+
+    #[test]
+    fn filtering_config_accepts_dcs() {
+        let host: IpAddr = "127.0.0.1".parse().unwrap();
+
+        let mut config = FilteringConfig::default();
+        config.whitelist_dcs = vec!["dc1".to_string(), "dc2".to_string()];
+        assert!(config.is_enabled());
+        assert!(config.accepts(host, Some("dc1")));
+        assert!(config.accepts(host, Some("dc2")));
+        assert!(!config.accepts(host, Some("dc3")));
+        assert!(!config.accepts(host, None));
+
+        config.whitelist_dcs.clear();
+        config.blacklist_dcs = vec!["dc2".to_string()];
+        assert!(config.accepts(host, Some("dc1")));
+        assert!(!config.accepts(host, Some("dc2")));
+        assert!(config.accepts(host, None));
+
+        // Host and datacenter filtering are combined
+        config.blacklist_hosts = vec![host];
+        assert!(!config.accepts(host, Some("dc1")));
+    }
+
+    #[test]
+    fn remote_dc_restrictions() {
+        let mut dc_awareness = DcAwareness::new("dc1".to_string());
+
+        // No remote hosts at all
+        assert!(!remote_dcs_permitted(&dc_awareness, Consistency::One, None));
+        assert!(!remote_dcs_permitted(
+            &dc_awareness,
+            Consistency::LocalQuorum,
+            None
+        ));
+
+        dc_awareness.used_hosts_per_remote_dc = 2;
+        assert!(remote_dcs_permitted(
+            &dc_awareness,
+            Consistency::Quorum,
+            None
+        ));
+        assert!(!remote_dcs_permitted(
+            &dc_awareness,
+            Consistency::LocalOne,
+            None
+        ));
+        assert!(!remote_dcs_permitted(
+            &dc_awareness,
+            Consistency::LocalQuorum,
+            None
+        ));
+        assert!(remote_dcs_permitted(
+            &dc_awareness,
+            Consistency::Quorum,
+            Some(SerialConsistency::Serial)
+        ));
+        assert!(!remote_dcs_permitted(
+            &dc_awareness,
+            Consistency::Quorum,
+            Some(SerialConsistency::LocalSerial)
+        ));
+
+        dc_awareness.allow_remote_dcs_for_local_cl = true;
+        assert!(remote_dcs_permitted(
+            &dc_awareness,
+            Consistency::LocalQuorum,
+            None
+        ));
+        assert!(remote_dcs_permitted(
+            &dc_awareness,
+            Consistency::Quorum,
+            Some(SerialConsistency::LocalSerial)
+        ));
+
+        // At most 2 hosts are taken from every remote datacenter
+        let mut used_hosts = HashMap::new();
+        let taken: Vec<bool> = [
+            Some("dc2"),
+            Some("dc3"),
+            Some("dc2"),
+            Some("dc2"),
+            None,
+            None,
+            None,
+        ]
+        .iter()
+        .map(|dc| take_remote_host(&dc_awareness, &mut used_hosts, *dc))
+        .collect();
+        assert_eq!(taken, vec![true, true, true, false, true, true, false]);
+    }
+
+    #[test]
+    fn preferred_nodes_first() {
+        // (name, rank)
+        let plan = [
+            ("b1", 1),
+            ("a1", 0),
+            ("c1", 2),
+            ("b2", 1),
+            ("a2", 0),
+            ("c2", 2),
+        ];
+        let pulled = &AtomicUsize::new(0);
+        let preferred_first = || {
+            let plan = plan.iter().inspect(move |_| {
+                pulled.fetch_add(1, Ordering::Relaxed);
+            });
+            PreferredFirst::new(plan, |node: &&(&str, u8)| node.1, 0)
+        };
+
+        let order: Vec<&str> = preferred_first().map(|node| node.0).collect();
+        assert_eq!(order, vec!["a1", "a2", "b1", "b2", "c1", "c2"]);
+
+        // Nodes of the best rank are yielded without reading the rest of the plan
+        pulled.store(0, Ordering::Relaxed);
+        let mut nodes = preferred_first();
+        assert_eq!(nodes.next().unwrap().0, "a1");
+        assert_eq!(pulled.load(Ordering::Relaxed), 2);
+        assert_eq!(nodes.next().unwrap().0, "a2");
+        assert_eq!(pulled.load(Ordering::Relaxed), 5);
+    }
+
+    #[test]
+    fn rack_aware_ordering() {
+        let rack_awareness = RackAwareness {
+            local_dc: "dc1".to_string(),
+            local_rack: "rack1".to_string(),
+        };
+        let rank = |dc, rack, is_replica| rack_aware_rank(&rack_awareness, dc, rack, is_replica);
+
+        let mut nodes = vec![
+            ("remote", rank(Some("dc2"), Some("rack1"), false)),
+            ("remote replica", rank(Some("dc2"), Some("rack1"), true)),
+            ("unknown dc", rank(None, Some("rack1"), false)),
+            ("local dc", rank(Some("dc1"), Some("rack2"), false)),
+            ("local rack", rank(Some("dc1"), Some("rack1"), false)),
+            ("local dc replica", rank(Some("dc1"), Some("rack2"), true)),
+            ("local rack replica", rank(Some("dc1"), Some("rack1"), true)),
+        ];
+        nodes.sort_by_key(|(_, rank)| *rank);
+
+        let order: Vec<&str> = nodes.iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            order,
+            vec![
+                "local rack replica",
+                "local dc replica",
+                "local rack",
+                "local dc",
+                "remote replica",
+                "remote",
+                "unknown dc",
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_plan_host_selection() {
+        assert_eq!(select_hosts(3, &[]), Vec::<usize>::new());
+        assert_eq!(select_hosts(3, &[2, 0]), vec![2, 0]);
+        // Out of range and repeated indices are skipped
+        assert_eq!(select_hosts(3, &[1, 3, 1, 100, 0]), vec![1, 0]);
+    }
+
+    unsafe extern "C" fn reversed_plan(
+        _routing_info: *const CassRoutingInfo,
+        _hosts: *const *const CassHostMetadata,
+        hosts_count: size_t,
+        plan: *mut size_t,
+        _data: *mut c_void,
+    ) -> size_t {
+        for i in 0..hosts_count {
+            *plan.add(i as usize) = hosts_count - 1 - i;
+        }
+        hosts_count
+    }
+
+    unsafe extern "C" fn data_cleanup(data: *mut c_void) {
+        let cleanups = &*(data as *const AtomicUsize);
+        cleanups.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn custom_policy_callback_lifetime() {
+        let cleanups = AtomicUsize::new(0);
+        let data = &cleanups as *const AtomicUsize as *mut c_void;
+
+        let mut config = LoadBalancingConfig::default();
+        assert_cass_error_eq!(
+            set_load_balance_custom(&mut config, None, Some(data_cleanup), data),
+            CassError::CASS_ERROR_LIB_BAD_PARAMS
+        );
+        assert!(config.custom_policy.is_none());
+
+        assert_cass_error_eq!(
+            set_load_balance_custom(&mut config, Some(reversed_plan), Some(data_cleanup), data),
+            CassError::CASS_OK
+        );
+        // Execution profiles and sessions share the callback with the config
+        let cloned = config.clone();
+        drop(config);
+        assert_eq!(cleanups.load(Ordering::SeqCst), 0);
+        drop(cloned);
+        assert_eq!(cleanups.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn routing_info_accessors() {
+        unsafe {
+            let mut routing_info = RoutingInfo {
+                consistency: Consistency::LocalQuorum,
+                ..Default::default()
+            };
+            let cass_routing_info = CassRoutingInfo::from(&routing_info);
+            let routing_info_raw = &cass_routing_info as *const CassRoutingInfo;
+
+            let mut keyspace: *const c_char = std::ptr::null();
+            let mut keyspace_length: size_t = 0;
+            let mut token: cass_int64_t = 0;
+            assert_cass_error_eq!(
+                cass_routing_info_keyspace(routing_info_raw, &mut keyspace, &mut keyspace_length),
+                CassError::CASS_ERROR_LIB_NULL_VALUE
+            );
+            assert_cass_error_eq!(
+                cass_routing_info_token(routing_info_raw, &mut token),
+                CassError::CASS_ERROR_LIB_NULL_VALUE
+            );
+            assert_eq!(
+                cass_routing_info_consistency(routing_info_raw),
+                CassConsistency::CASS_CONSISTENCY_LOCAL_QUORUM
+            );
+
+            routing_info.keyspace = Some("ks");
+            routing_info.token = Some(Token { value: 42 });
+            let cass_routing_info = CassRoutingInfo::from(&routing_info);
+            let routing_info_raw = &cass_routing_info as *const CassRoutingInfo;
+            assert_cass_error_eq!(
+                cass_routing_info_keyspace(routing_info_raw, &mut keyspace, &mut keyspace_length),
+                CassError::CASS_OK
+            );
+            assert_eq!(ptr_to_cstr_n(keyspace, keyspace_length), Some("ks"));
+            assert_cass_error_eq!(
+                cass_routing_info_token(routing_info_raw, &mut token),
+                CassError::CASS_OK
+            );
+            assert_eq!(token, 42);
+        }
+    }
+}
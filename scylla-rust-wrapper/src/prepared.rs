@@ -31,5 +31,6 @@ pub unsafe extern "C" fn cass_prepared_bind(
         paging_state: None,
         request_timeout_ms: None,
         exec_profile: None,
+        target_host: None,
     }))
 }
@@ -0,0 +1,149 @@
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// Limits of in-flight requests of a session. Nothing is limited by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct RequestThrottlingConfig {
+    pub(crate) max_concurrent_requests: Option<NonZeroUsize>,
+    pub(crate) high_water_mark: Option<NonZeroUsize>,
+    pub(crate) low_water_mark: Option<usize>,
+}
+
+impl RequestThrottlingConfig {
+    pub(crate) fn is_needed(&self) -> bool {
+        self.max_concurrent_requests.is_some() || self.high_water_mark.is_some()
+    }
+
+    // The ratio of the CPP driver's defaults.
+    fn low_water_mark(&self) -> usize {
+        match (self.low_water_mark, self.high_water_mark) {
+            (Some(low_water_mark), _) => low_water_mark,
+            (None, Some(high_water_mark)) => high_water_mark.get() / 2,
+            (None, None) => 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct RequestThrottler {
+    config: RequestThrottlingConfig,
+    in_flight: AtomicUsize,
+    // Set when the high water mark is exceeded. New requests are rejected
+    // until the in-flight requests drain to the low water mark.
+    paused: AtomicBool,
+}
+
+impl RequestThrottler {
+    pub(crate) fn new(config: RequestThrottlingConfig) -> Self {
+        Self {
+            config,
+            in_flight: AtomicUsize::new(0),
+            paused: AtomicBool::new(false),
+        }
+    }
+
+    pub(crate) fn try_acquire(self: &Arc<Self>) -> Option<RequestPermit> {
+        if self.paused.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let in_flight = self.in_flight.fetch_add(1, Ordering::AcqRel) + 1;
+        let permit = RequestPermit(self.clone());
+        if let Some(max_concurrent_requests) = self.config.max_concurrent_requests {
+            if in_flight > max_concurrent_requests.get() {
+                return None;
+            }
+        }
+        if let Some(high_water_mark) = self.config.high_water_mark {
+            if in_flight > high_water_mark.get() {
+                // Pausing before the permit is dropped guarantees that some release
+                // observes the drained state and resumes the session.
+                self.paused.store(true, Ordering::Release);
+                return None;
+            }
+        }
+
+        Some(permit)
+    }
+
+    fn release(&self) {
+        let in_flight = self.in_flight.fetch_sub(1, Ordering::AcqRel) - 1;
+        if in_flight <= self.config.low_water_mark() {
+            self.paused.store(false, Ordering::Release);
+        }
+    }
+}
+
+// Counts a request as in flight until dropped.
+#[derive(Debug)]
+pub(crate) struct RequestPermit(Arc<RequestThrottler>);
+
+impl Drop for RequestPermit {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn throttler(
+        max_concurrent_requests: Option<usize>,
+        high_water_mark: Option<usize>,
+        low_water_mark: Option<usize>,
+    ) -> Arc<RequestThrottler> {
+        Arc::new(RequestThrottler::new(RequestThrottlingConfig {
+            max_concurrent_requests: max_concurrent_requests.and_then(NonZeroUsize::new),
+            high_water_mark: high_water_mark.and_then(NonZeroUsize::new),
+            low_water_mark,
+        }))
+    }
+
+    #[test]
+    fn max_concurrent_requests() {
+        let throttler = throttler(Some(2), None, None);
+
+        let first = throttler.try_acquire().unwrap();
+        let second = throttler.try_acquire().unwrap();
+        assert!(throttler.try_acquire().is_none());
+
+        // A finished request frees its slot right away
+        drop(first);
+        let third = throttler.try_acquire().unwrap();
+        assert!(throttler.try_acquire().is_none());
+
+        drop(second);
+        drop(third);
+        assert_eq!(throttler.in_flight.load(Ordering::Acquire), 0);
+    }
+
+    #[test]
+    fn water_marks() {
+        let throttler = throttler(None, Some(3), Some(1));
+
+        let mut permits: Vec<_> = (0..3).map(|_| throttler.try_acquire().unwrap()).collect();
+        // Exceeding the high water mark pauses the session...
+        assert!(throttler.try_acquire().is_none());
+        permits.pop();
+        // ...until the requests drain to the low water mark
+        assert!(throttler.try_acquire().is_none());
+        permits.pop();
+        permits.push(throttler.try_acquire().unwrap());
+        permits.push(throttler.try_acquire().unwrap());
+        assert_eq!(permits.len(), 3);
+    }
+
+    #[test]
+    fn default_low_water_mark() {
+        let throttler = throttler(None, Some(4), None);
+
+        let mut permits: Vec<_> = (0..4).map(|_| throttler.try_acquire().unwrap()).collect();
+        assert!(throttler.try_acquire().is_none());
+        permits.truncate(3);
+        assert!(throttler.try_acquire().is_none());
+        permits.truncate(2);
+        assert!(throttler.try_acquire().is_some());
+    }
+}
@@ -0,0 +1,706 @@
+use libc::{sockaddr, sockaddr_in, sockaddr_in6, socklen_t};
+use rand::Rng;
+use std::convert::TryInto;
+use std::ffi::CStr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::os::raw::c_char;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+
+const DNS_PORT: u16 = 53;
+const RESOLV_CONF: &str = "/etc/resolv.conf";
+const TYPE_SRV: u16 = 33;
+const CLASS_IN: u16 = 1;
+// Large enough for a non-EDNS UDP response, longer ones are truncated.
+const MAX_UDP_RESPONSE_SIZE: usize = 512;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ResolveConfig {
+    // Bounds every lookup, whichever name servers it goes through.
+    pub(crate) timeout: Duration,
+    pub(crate) use_srv_records: bool,
+    // Whether the hostnames of the nodes are reverse-resolved.
+    pub(crate) use_hostname_resolution: bool,
+    // Name servers the SRV records are queried from in turn, empty for the ones
+    // in /etc/resolv.conf. Addresses and hostnames come from the system resolver.
+    pub(crate) nameservers: Vec<SocketAddr>,
+}
+
+impl Default for ResolveConfig {
+    // Defaults of the CPP driver.
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_millis(2000),
+            use_srv_records: false,
+            use_hostname_resolution: false,
+            nameservers: Vec::new(),
+        }
+    }
+}
+
+impl ResolveConfig {
+    fn nameservers(&self) -> Vec<SocketAddr> {
+        if !self.nameservers.is_empty() {
+            return self.nameservers.clone();
+        }
+
+        let nameservers: Vec<SocketAddr> = std::fs::read_to_string(RESOLV_CONF)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(
+                |line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+                    ["nameserver", address, ..] => address.parse::<IpAddr>().ok(),
+                    _ => None,
+                },
+            )
+            .map(|address| SocketAddr::new(address, DNS_PORT))
+            .collect();
+        if nameservers.is_empty() {
+            vec![SocketAddr::new(Ipv4Addr::LOCALHOST.into(), DNS_PORT)]
+        } else {
+            nameservers
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SrvRecord {
+    pub(crate) priority: u16,
+    pub(crate) weight: u16,
+    pub(crate) port: u16,
+    pub(crate) target: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Response {
+    truncated: bool,
+    records: Vec<SrvRecord>,
+}
+
+// Looks up the SRV records of `name`, in the order they are to be tried.
+pub(crate) async fn resolve_srv(
+    name: &str,
+    config: &ResolveConfig,
+) -> Result<Vec<SrvRecord>, String> {
+    let records = lookup(name, config).await?;
+    Ok(order_srv_records(records, &mut rand::thread_rng()))
+}
+
+// Orders the records by priority, and the records of a priority by weighted random
+// selection, as described in RFC 2782. Records of weight 0 go first among their
+// priority, so that they only have a small chance of being selected before the others.
+fn order_srv_records(mut records: Vec<SrvRecord>, rng: &mut impl Rng) -> Vec<SrvRecord> {
+    records.sort_by_key(|record| (record.priority, record.weight != 0));
+
+    let mut ordered = Vec::with_capacity(records.len());
+    let mut records = records.into_iter().peekable();
+    while let Some(first) = records.next() {
+        let priority = first.priority;
+        let mut candidates = vec![first];
+        while let Some(record) = records.next_if(|record| record.priority == priority) {
+            candidates.push(record);
+        }
+
+        while !candidates.is_empty() {
+            let total_weight: u32 = candidates
+                .iter()
+                .map(|record| u32::from(record.weight))
+                .sum();
+            let selected_weight = rng.gen_range(0..=total_weight);
+            let mut running_weight = 0;
+            let selected = candidates
+                .iter()
+                .position(|record| {
+                    running_weight += u32::from(record.weight);
+                    running_weight >= selected_weight
+                })
+                .unwrap_or(0);
+            ordered.push(candidates.remove(selected));
+        }
+    }
+    ordered
+}
+
+// Looks up the addresses of `host` with the system resolver.
+pub(crate) async fn resolve_host(
+    host: &str,
+    port: u16,
+    config: &ResolveConfig,
+) -> Result<Vec<SocketAddr>, String> {
+    match tokio::time::timeout(config.timeout, tokio::net::lookup_host((host, port))).await {
+        Ok(Ok(addresses)) => Ok(addresses.collect()),
+        Ok(Err(err)) => Err(format!("Unable to resolve {}: {}", host, err)),
+        Err(_) => Err(format!("Unable to resolve {}: timed out", host)),
+    }
+}
+
+// Looks up the hostname of `address` with the system resolver.
+pub(crate) async fn reverse_resolve(
+    address: IpAddr,
+    config: &ResolveConfig,
+) -> Result<String, String> {
+    // getnameinfo() blocks, and cannot be cancelled once the timeout expires.
+    let lookup = tokio::task::spawn_blocking(move || getnameinfo(address));
+    match tokio::time::timeout(config.timeout, lookup).await {
+        Ok(Ok(Some(hostname))) => Ok(hostname),
+        Ok(_) => Err(format!("No hostname found for {}", address)),
+        Err(_) => Err(format!("Unable to reverse resolve {}: timed out", address)),
+    }
+}
+
+fn getnameinfo(address: IpAddr) -> Option<String> {
+    const NI_MAXHOST: usize = 1025;
+    let mut hostname = [0 as c_char; NI_MAXHOST];
+
+    let rc = unsafe {
+        match address {
+            IpAddr::V4(address) => {
+                let mut addr: sockaddr_in = std::mem::zeroed();
+                addr.sin_family = libc::AF_INET as libc::sa_family_t;
+                addr.sin_addr.s_addr = u32::from_ne_bytes(address.octets());
+                libc::getnameinfo(
+                    &addr as *const sockaddr_in as *const sockaddr,
+                    std::mem::size_of::<sockaddr_in>() as socklen_t,
+                    hostname.as_mut_ptr(),
+                    NI_MAXHOST as socklen_t,
+                    std::ptr::null_mut(),
+                    0,
+                    libc::NI_NAMEREQD,
+                )
+            }
+            IpAddr::V6(address) => {
+                let mut addr: sockaddr_in6 = std::mem::zeroed();
+                addr.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+                addr.sin6_addr.s6_addr = address.octets();
+                libc::getnameinfo(
+                    &addr as *const sockaddr_in6 as *const sockaddr,
+                    std::mem::size_of::<sockaddr_in6>() as socklen_t,
+                    hostname.as_mut_ptr(),
+                    NI_MAXHOST as socklen_t,
+                    std::ptr::null_mut(),
+                    0,
+                    libc::NI_NAMEREQD,
+                )
+            }
+        }
+    };
+    if rc != 0 {
+        return None;
+    }
+
+    unsafe { CStr::from_ptr(hostname.as_ptr()) }
+        .to_str()
+        .ok()
+        .map(ToOwned::to_owned)
+}
+
+// Queries the name servers in turn until one of them answers. The timeout is split
+// evenly between them, so that an unresponsive one does not use it all up.
+async fn lookup(name: &str, config: &ResolveConfig) -> Result<Vec<SrvRecord>, String> {
+    let query_id: u16 = rand::random();
+    let query = build_query(query_id, name)?;
+    let nameservers = config.nameservers();
+    let nameserver_timeout = config.timeout / nameservers.len() as u32;
+
+    let mut errors = Vec::with_capacity(nameservers.len());
+    for nameserver in nameservers {
+        let response = exchange(nameserver, query_id, &query);
+        match tokio::time::timeout(nameserver_timeout, response).await {
+            Ok(Ok(records)) => return Ok(records),
+            Ok(Err(err)) => errors.push(format!("{}: {}", nameserver, err)),
+            Err(_) => errors.push(format!("{}: timed out", nameserver)),
+        }
+    }
+    Err(format!("Unable to resolve {}: {}", name, errors.join(", ")))
+}
+
+// Sends the query over UDP, and once more over TCP if the response is truncated.
+async fn exchange(
+    nameserver: SocketAddr,
+    query_id: u16,
+    query: &[u8],
+) -> Result<Vec<SrvRecord>, String> {
+    let local_address: SocketAddr = match nameserver {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(local_address)
+        .await
+        .map_err(|err| err.to_string())?;
+    socket
+        .connect(nameserver)
+        .await
+        .map_err(|err| err.to_string())?;
+    socket.send(query).await.map_err(|err| err.to_string())?;
+
+    let mut message = [0u8; MAX_UDP_RESPONSE_SIZE];
+    let response = loop {
+        let len = socket
+            .recv(&mut message)
+            .await
+            .map_err(|err| err.to_string())?;
+        // Stray responses to other queries are skipped.
+        if let Some(response) = parse_response(query_id, &message[..len]) {
+            break response?;
+        }
+    };
+    if !response.truncated {
+        return Ok(response.records);
+    }
+
+    let mut stream = TcpStream::connect(nameserver)
+        .await
+        .map_err(|err| err.to_string())?;
+    let mut request = Vec::with_capacity(2 + query.len());
+    request.extend_from_slice(&(query.len() as u16).to_be_bytes());
+    request.extend_from_slice(query);
+    stream
+        .write_all(&request)
+        .await
+        .map_err(|err| err.to_string())?;
+    let len = stream.read_u16().await.map_err(|err| err.to_string())?;
+    let mut message = vec![0u8; len as usize];
+    stream
+        .read_exact(&mut message)
+        .await
+        .map_err(|err| err.to_string())?;
+    match parse_response(query_id, &message) {
+        Some(response) => Ok(response?.records),
+        None => Err("Unexpected response from name server".to_string()),
+    }
+}
+
+fn build_query(id: u16, name: &str) -> Result<Vec<u8>, String> {
+    let mut query = Vec::with_capacity(MAX_UDP_RESPONSE_SIZE);
+    query.extend_from_slice(&id.to_be_bytes());
+    // Recursion desired, one question.
+    query.extend_from_slice(&[0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(format!("Invalid DNS name {}", name));
+        }
+        query.push(label.len() as u8);
+        query.extend_from_slice(label.as_bytes());
+    }
+    query.push(0);
+    query.extend_from_slice(&TYPE_SRV.to_be_bytes());
+    query.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Ok(query)
+}
+
+// Returns `None` if the message is not a response to the query.
+fn parse_response(query_id: u16, message: &[u8]) -> Option<Result<Response, String>> {
+    let read_u16 = |offset: usize| -> Option<u16> {
+        Some(u16::from_be_bytes(
+            message.get(offset..offset + 2)?.try_into().ok()?,
+        ))
+    };
+
+    let flags = read_u16(2)?;
+    if read_u16(0)? != query_id || flags & 0x8000 == 0 {
+        return None;
+    }
+
+    let parse = || -> Option<Result<Response, String>> {
+        let truncated = flags & 0x0200 != 0;
+        let rcode = flags & 0x000f;
+        if rcode != 0 {
+            return Some(Err(format!("Name server responded with code {}", rcode)));
+        }
+
+        let questions = read_u16(4)?;
+        let answers = read_u16(6)?;
+        let mut offset = 12;
+        for _ in 0..questions {
+            offset = read_name(message, offset)?.1 + 4;
+        }
+
+        let mut records = Vec::with_capacity(answers as usize);
+        for _ in 0..answers {
+            // The answers of a truncated response may be cut off anywhere.
+            let (record_type, data, data_len) = match read_name(message, offset) {
+                Some((_, name_end)) => {
+                    let record_type = read_u16(name_end);
+                    let data_len = read_u16(name_end + 8);
+                    match (record_type, data_len) {
+                        (Some(record_type), Some(data_len)) => {
+                            (record_type, name_end + 10, data_len as usize)
+                        }
+                        _ if truncated => break,
+                        _ => return None,
+                    }
+                }
+                None if truncated => break,
+                None => return None,
+            };
+            offset = data + data_len;
+            if offset > message.len() {
+                if truncated {
+                    break;
+                }
+                return None;
+            }
+            // Other records, e.g. CNAMEs leading to the requested ones, are skipped.
+            if record_type != TYPE_SRV {
+                continue;
+            }
+            records.push(SrvRecord {
+                priority: read_u16(data)?,
+                weight: read_u16(data + 2)?,
+                port: read_u16(data + 4)?,
+                target: read_name(message, data + 6)?.0,
+            });
+        }
+        Some(Ok(Response { truncated, records }))
+    };
+
+    Some(parse().unwrap_or_else(|| Err("Malformed response from name server".to_string())))
+}
+
+// Reads a possibly compressed name, returning it with the offset right after it.
+fn read_name(message: &[u8], mut offset: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    // Bounds the number of compression pointers followed, so that loops are rejected.
+    for _ in 0..message.len() {
+        let len = *message.get(offset)? as usize;
+        match len & 0xc0 {
+            0x00 if len == 0 => {
+                return Some((labels.join("."), end.unwrap_or(offset + 1)));
+            }
+            0x00 => {
+                let label = message.get(offset + 1..offset + 1 + len)?;
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                offset += 1 + len;
+            }
+            0xc0 => {
+                let pointer = ((len & 0x3f) << 8) | *message.get(offset + 1)? as usize;
+                end.get_or_insert(offset + 2);
+                offset = pointer;
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use tokio::net::TcpListener;
+
+    #[derive(Default)]
+    struct Zone {
+        srv_records: HashMap<String, Vec<SrvRecord>>,
+        // Whether UDP responses are truncated, so that the client has to retry over TCP.
+        truncate_udp: bool,
+    }
+
+    // Serves the SRV records of its zone over UDP and TCP, compressing the names
+    // of the answers against the question, as real name servers do.
+    #[derive(Clone)]
+    pub(crate) struct StubResolver {
+        pub(crate) address: SocketAddr,
+        zone: Arc<Mutex<Zone>>,
+    }
+
+    impl StubResolver {
+        pub(crate) async fn run() -> Self {
+            let zone: Arc<Mutex<Zone>> = Default::default();
+            let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+            let address = socket.local_addr().unwrap();
+            let listener = TcpListener::bind(address).await.unwrap();
+
+            let udp_zone = zone.clone();
+            tokio::spawn(async move {
+                let mut query = [0u8; MAX_UDP_RESPONSE_SIZE];
+                while let Ok((len, client)) = socket.recv_from(&mut query).await {
+                    let response = respond(&udp_zone, &query[..len], true);
+                    let _ = socket.send_to(&response, client).await;
+                }
+            });
+            let tcp_zone = zone.clone();
+            tokio::spawn(async move {
+                while let Ok((mut stream, _)) = listener.accept().await {
+                    let zone = tcp_zone.clone();
+                    tokio::spawn(async move {
+                        let len = stream.read_u16().await.unwrap();
+                        let mut query = vec![0u8; len as usize];
+                        stream.read_exact(&mut query).await.unwrap();
+                        let response = respond(&zone, &query, false);
+                        stream
+                            .write_all(&(response.len() as u16).to_be_bytes())
+                            .await
+                            .unwrap();
+                        stream.write_all(&response).await.unwrap();
+                    });
+                }
+            });
+
+            Self { address, zone }
+        }
+
+        pub(crate) fn set_srv_records(&self, name: &str, records: Vec<SrvRecord>) {
+            let mut zone = self.zone.lock().unwrap();
+            zone.srv_records.insert(name.to_string(), records);
+        }
+
+        fn set_truncate_udp(&self, truncate_udp: bool) {
+            self.zone.lock().unwrap().truncate_udp = truncate_udp;
+        }
+
+        pub(crate) fn config(&self) -> ResolveConfig {
+            ResolveConfig {
+                timeout: Duration::from_millis(500),
+                nameservers: vec![self.address],
+                ..Default::default()
+            }
+        }
+    }
+
+    fn respond(zone: &Mutex<Zone>, query: &[u8], over_udp: bool) -> Vec<u8> {
+        let zone = zone.lock().unwrap();
+        let (name, question_end) = read_name(query, 12).unwrap();
+        let record_type =
+            u16::from_be_bytes(query[question_end..question_end + 2].try_into().unwrap());
+
+        let mut response = query[..question_end + 4].to_vec();
+        response[2] |= 0x80;
+        if over_udp && zone.truncate_udp {
+            response[2] |= 0x02;
+            return response;
+        }
+
+        let answers: Vec<Vec<u8>> = match record_type {
+            TYPE_SRV => zone
+                .srv_records
+                .get(&name)
+                .into_iter()
+                .flatten()
+                .map(|record| {
+                    let mut data = Vec::new();
+                    data.extend_from_slice(&record.priority.to_be_bytes());
+                    data.extend_from_slice(&record.weight.to_be_bytes());
+                    data.extend_from_slice(&record.port.to_be_bytes());
+                    data.extend_from_slice(&encode_name(&record.target));
+                    data
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        response[7] = answers.len() as u8;
+        for data in answers {
+            // Name pointing at the question
+            response.extend_from_slice(&[0xc0, 12]);
+            response.extend_from_slice(&record_type.to_be_bytes());
+            response.extend_from_slice(&CLASS_IN.to_be_bytes());
+            response.extend_from_slice(&60u32.to_be_bytes());
+            response.extend_from_slice(&(data.len() as u16).to_be_bytes());
+            response.extend_from_slice(&data);
+        }
+        response
+    }
+
+    fn encode_name(name: &str) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        for label in name.split('.') {
+            encoded.push(label.len() as u8);
+            encoded.extend_from_slice(label.as_bytes());
+        }
+        encoded.push(0);
+        encoded
+    }
+
+    fn srv(priority: u16, weight: u16, port: u16, target: &str) -> SrvRecord {
+        SrvRecord {
+            priority,
+            weight,
+            port,
+            target: target.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(1000)]
+    async fn srv_lookup() {
+        let resolver = StubResolver::run().await;
+        resolver.set_srv_records(
+            "_cql._tcp.example.com",
+            vec![
+                srv(20, 0, 9044, "node3.example.com"),
+                srv(10, 5, 9042, "node1.example.com"),
+                srv(10, 50, 9043, "node2.example.com"),
+            ],
+        );
+
+        let records = resolve_srv("_cql._tcp.example.com", &resolver.config())
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(records[..2].contains(&srv(10, 5, 9042, "node1.example.com")));
+        assert!(records[..2].contains(&srv(10, 50, 9043, "node2.example.com")));
+        assert_eq!(records[2], srv(20, 0, 9044, "node3.example.com"));
+    }
+
+    #[test]
+    fn srv_records_order() {
+        let mut rng = rand::thread_rng();
+        let records = vec![
+            srv(20, 1, 9042, "node4.example.com"),
+            srv(10, 0, 9042, "node1.example.com"),
+            srv(10, 90, 9042, "node2.example.com"),
+            srv(10, 10, 9042, "node3.example.com"),
+        ];
+
+        let mut node2_first = 0;
+        for _ in 0..1000 {
+            let ordered = order_srv_records(records.clone(), &mut rng);
+            // Priorities are never mixed up
+            let priorities: Vec<u16> = ordered.iter().map(|record| record.priority).collect();
+            assert_eq!(priorities, vec![10, 10, 10, 20]);
+            if ordered[0].target == "node2.example.com" {
+                node2_first += 1;
+            }
+        }
+        // 90 chances out of 101
+        assert!((840..=940).contains(&node2_first), "{}", node2_first);
+
+        // Records of weight 0 only are still all returned
+        let records = vec![srv(10, 0, 9042, "node1.example.com"); 3];
+        assert_eq!(order_srv_records(records.clone(), &mut rng), records);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(5000)]
+    async fn host_and_reverse_lookup() {
+        let config = ResolveConfig::default();
+        let localhost: Vec<IpAddr> = resolve_host("localhost", 9042, &config)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|address| address.ip())
+            .collect();
+        assert!(localhost.iter().all(IpAddr::is_loopback), "{:?}", localhost);
+        // IP literals are not looked up
+        assert_eq!(
+            resolve_host("10.0.0.2", 9043, &config).await.unwrap(),
+            vec!["10.0.0.2:9043".parse::<SocketAddr>().unwrap()]
+        );
+        assert!(resolve_host("node1.invalid", 9042, &config).await.is_err());
+
+        let hostname = reverse_resolve(Ipv4Addr::LOCALHOST.into(), &config)
+            .await
+            .unwrap();
+        assert!(hostname.starts_with("localhost"), "{}", hostname);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(1000)]
+    async fn truncated_responses_are_retried_over_tcp() {
+        let resolver = StubResolver::run().await;
+        let records: Vec<SrvRecord> = (0..30)
+            .map(|i| srv(10, 0, 9042, &format!("node{}.dc1.cluster.example.com", i)))
+            .collect();
+        resolver.set_srv_records("_cql._tcp.example.com", records.clone());
+        resolver.set_truncate_udp(true);
+
+        let mut resolved = resolve_srv("_cql._tcp.example.com", &resolver.config())
+            .await
+            .unwrap();
+        resolved.sort_by(|a, b| a.target.cmp(&b.target));
+        let mut expected = records;
+        expected.sort_by(|a, b| a.target.cmp(&b.target));
+        assert_eq!(resolved, expected);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(1000)]
+    async fn unresponsive_nameservers_are_skipped() {
+        let resolver = StubResolver::run().await;
+        resolver.set_srv_records(
+            "_cql._tcp.example.com",
+            vec![srv(10, 10, 9042, "node1.example.com")],
+        );
+        // Nothing answers on this socket
+        let silent = std::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let config = ResolveConfig {
+            nameservers: vec![silent.local_addr().unwrap(), resolver.address],
+            ..resolver.config()
+        };
+
+        assert_eq!(
+            resolve_srv("_cql._tcp.example.com", &config).await.unwrap(),
+            vec![srv(10, 10, 9042, "node1.example.com")]
+        );
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(1000)]
+    async fn lookup_timeout() {
+        // Nothing answers on this socket
+        let silent = std::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let config = ResolveConfig {
+            timeout: Duration::from_millis(100),
+            nameservers: vec![silent.local_addr().unwrap()],
+            ..Default::default()
+        };
+
+        let err = resolve_srv("_cql._tcp.example.com", &config)
+            .await
+            .unwrap_err();
+        assert!(err.ends_with("timed out"), "{}", err);
+    }
+
+    #[test]
+    fn malformed_responses() {
+        let query = build_query(7, "_cql._tcp.example.com").unwrap();
+        assert!(build_query(7, "_cql.._tcp").is_err());
+
+        // Not a response, or a response to another query
+        assert_eq!(parse_response(7, &query), None);
+        let mut response = query.clone();
+        response[2] |= 0x80;
+        assert_eq!(parse_response(8, &response), None);
+
+        // No records
+        assert_eq!(
+            parse_response(7, &response),
+            Some(Ok(Response {
+                truncated: false,
+                records: vec![]
+            }))
+        );
+
+        // Error code
+        let mut error = response.clone();
+        error[3] |= 0x03;
+        assert!(matches!(parse_response(7, &error), Some(Err(_))));
+
+        // Truncated answer
+        let mut truncated = response.clone();
+        truncated[7] = 1;
+        truncated.extend_from_slice(&[0xc0, 12, 0x00]);
+        assert!(matches!(parse_response(7, &truncated), Some(Err(_))));
+
+        // ... unless the name server marked it so
+        truncated[2] |= 0x02;
+        assert_eq!(
+            parse_response(7, &truncated),
+            Some(Ok(Response {
+                truncated: true,
+                records: vec![]
+            }))
+        );
+
+        // Compression loop
+        let mut looped = response;
+        looped[7] = 1;
+        let loop_offset = looped.len() as u8;
+        looped.extend_from_slice(&[0xc0, loop_offset]);
+        assert!(matches!(parse_response(7, &looped), Some(Err(_))));
+    }
+}
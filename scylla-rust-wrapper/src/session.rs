@@ -6,13 +6,19 @@ use crate::cluster::build_session_builder;
 use crate::cluster::CassCluster;
 use crate::exec_profile::{CassExecProfile, ExecProfileName, PerStatementExecProfile};
 use crate::future::{CassFuture, CassFutureResult, CassResultValue};
+use crate::load_balancing::TargetHostPolicy;
 use crate::metadata::create_table_metadata;
 use crate::metadata::{CassKeyspaceMeta, CassMaterializedViewMeta, CassSchemaMeta};
 use crate::query_result::Value::{CollectionValue, RegularValue};
 use crate::query_result::{CassResult, CassResultData, CassRow, CassValue, Collection, Value};
+use crate::request_throttler::{RequestPermit, RequestThrottler};
+use crate::ssl::{reload_session_ssl, CassSsl};
 use crate::statement::CassStatement;
 use crate::statement::Statement;
+use crate::timestamp_generator::CassTimestampGen;
+use crate::tls_tunnel::node_address;
 use crate::types::{cass_uint64_t, size_t};
+use openssl::ssl::SslContext;
 use scylla::frame::response::result::{CqlValue, Row};
 use scylla::frame::types::Consistency;
 use scylla::query::Query;
@@ -21,6 +27,7 @@ use scylla::transport::execution_profile::ExecutionProfileHandle;
 use scylla::{QueryResult, Session, SessionBuilder};
 use std::collections::HashMap;
 use std::future::Future;
+use std::net::SocketAddr;
 use std::ops::Deref;
 use std::os::raw::c_char;
 use std::sync::Arc;
@@ -30,6 +37,9 @@ use tokio::sync::RwLock;
 pub struct CassSessionInner {
     session: Session,
     exec_profile_map: HashMap<ExecProfileName, ExecutionProfileHandle>,
+    ssl_context: Option<SslContext>,
+    request_throttler: Option<Arc<RequestThrottler>>,
+    timestamp_generator: Arc<CassTimestampGen>,
 }
 
 impl CassSessionInner {
@@ -65,6 +75,77 @@ impl CassSessionInner {
         }
     }
 
+    // Fails a request right away if the session already has too many requests in flight.
+    // The returned permit must be held until the request completes.
+    fn acquire_request_permit(&self) -> Result<Option<RequestPermit>, (CassError, String)> {
+        match &self.request_throttler {
+            None => Ok(None),
+            Some(throttler) => throttler.try_acquire().map(Some).ok_or_else(|| {
+                (
+                    CassError::CASS_ERROR_LIB_REQUEST_QUEUE_FULL,
+                    "The request queue has reached capacity".msg(),
+                )
+            }),
+        }
+    }
+
+    // Acquires the permit of a request as it is issued, so that a full queue fails the request
+    // with a ready future. No permit is acquired if the session is being connected or closed,
+    // in which case the request acquires it once it gets hold of the session.
+    fn acquire_request_permit_now(
+        session_opt: &CassSession,
+    ) -> Result<Option<RequestPermit>, (CassError, String)> {
+        match session_opt.try_read() {
+            Ok(session_guard) => match session_guard.as_ref() {
+                Some(cass_session_inner) => cass_session_inner.acquire_request_permit(),
+                None => Ok(None),
+            },
+            Err(_) => Ok(None),
+        }
+    }
+
+    // Derives the profile of a statement sent to a specific node, so that the node
+    // is the only one in the query plan.
+    fn target_host_profile_handle(
+        &self,
+        handle: Option<ExecutionProfileHandle>,
+        target_host: SocketAddr,
+    ) -> Result<ExecutionProfileHandle, (CassError, String)> {
+        let cluster_data = self.session.get_cluster_data();
+        match cluster_data
+            .get_nodes_info()
+            .iter()
+            .find(|node| node_address(node.address) == target_host)
+        {
+            None => {
+                return Err((
+                    CassError::CASS_ERROR_LIB_NO_HOSTS_AVAILABLE,
+                    format!("Host {} is not known to the driver", target_host),
+                ))
+            }
+            Some(node) if node.is_down() => {
+                return Err((
+                    CassError::CASS_ERROR_LIB_NO_HOSTS_AVAILABLE,
+                    format!("Host {} is down", target_host),
+                ))
+            }
+            Some(_) => (),
+        }
+
+        let profile_builder = match handle {
+            Some(handle) => handle.pointee_to_builder(),
+            None => self
+                .session
+                .get_default_execution_profile_handle()
+                .pointee_to_builder(),
+        };
+
+        Ok(profile_builder
+            .load_balancing_policy(Arc::new(TargetHostPolicy::new(target_host)))
+            .build()
+            .into_handle())
+    }
+
     fn connect(
         // This reference is 'static because this is the only was of assuring the borrow checker
         // that holding it in our returned future is sound. Ideally, we would prefer to have
@@ -74,21 +155,33 @@ impl CassSessionInner {
         cluster: &CassCluster,
         keyspace: Option<String>,
     ) -> *const CassFuture {
-        let session_builder = build_session_builder(cluster);
+        // The context is kept by the session, so that its TLS material can be reloaded.
+        let ssl_context = cluster.session_ssl_context();
+        let session_builder = build_session_builder(cluster, ssl_context.clone());
         let exec_profile_map = cluster.execution_profile_map().clone();
+        let request_throttler = Some(*cluster.request_throttling_config())
+            .filter(|config| config.is_needed())
+            .map(|config| Arc::new(RequestThrottler::new(config)));
+        let timestamp_generator = cluster.timestamp_generator().clone();
 
         CassFuture::make_raw(Self::connect_fut(
             session_opt,
             session_builder,
             exec_profile_map,
+            ssl_context,
+            request_throttler,
+            timestamp_generator,
             keyspace,
         ))
     }
 
     async fn connect_fut(
         session_opt: &RwLock<Option<CassSessionInner>>,
-        session_builder_fut: impl Future<Output = SessionBuilder>,
+        session_builder_fut: impl Future<Output = Result<SessionBuilder, (CassError, String)>>,
         exec_profile_builder_map: HashMap<ExecProfileName, CassExecProfile>,
+        ssl_context: Option<SslContext>,
+        request_throttler: Option<Arc<RequestThrottler>>,
+        timestamp_generator: Arc<CassTimestampGen>,
         keyspace: Option<String>,
     ) -> CassFutureResult {
         // This can sleep for a long time, but only if someone connects/closes session
@@ -105,7 +198,7 @@ impl CassSessionInner {
             exec_profile_map.insert(name, builder.build().await.into_handle());
         }
 
-        let mut session_builder = session_builder_fut.await;
+        let mut session_builder = session_builder_fut.await?;
         if let Some(keyspace) = keyspace {
             session_builder = session_builder.use_keyspace(keyspace, false);
         }
@@ -118,6 +211,9 @@ impl CassSessionInner {
         *session_guard = Some(CassSessionInner {
             session,
             exec_profile_map,
+            ssl_context,
+            request_throttler,
+            timestamp_generator,
         });
         Ok(CassResultValue::Empty)
     }
@@ -180,6 +276,11 @@ pub unsafe extern "C" fn cass_session_execute_batch(
     #[allow(unused, clippy::let_unit_value)]
     let batch_from_raw = (); // Hardening shadow to avoid use-after-free.
 
+    let permit = match CassSessionInner::acquire_request_permit_now(session_opt) {
+        Ok(permit) => permit,
+        Err(err) => return CassFuture::make_ready_raw(Err(err)),
+    };
+
     let future = async move {
         let session_guard = session_opt.read().await;
         if session_guard.is_none() {
@@ -191,14 +292,21 @@ pub unsafe extern "C" fn cass_session_execute_batch(
 
         let cass_session_inner = &session_guard.as_ref().unwrap();
         let session = &cass_session_inner.session;
+        let _permit = match permit {
+            Some(permit) => Some(permit),
+            None => cass_session_inner.acquire_request_permit()?,
+        };
 
         let handle = cass_session_inner
             .get_or_resolve_profile_handle(batch_exec_profile.as_ref())
             .await?;
 
-        Arc::make_mut(&mut state)
-            .batch
-            .set_execution_profile_handle(handle);
+        let batch = &mut Arc::make_mut(&mut state).batch;
+        batch.set_execution_profile_handle(handle);
+        // A timestamp set on the batch takes precedence over the generated one.
+        if batch.get_timestamp().is_none() {
+            batch.set_timestamp(cass_session_inner.timestamp_generator.next_timestamp());
+        }
 
         let query_res = session.batch(&state.batch, &state.bound_values).await;
         match query_res {
@@ -249,9 +357,15 @@ pub unsafe extern "C" fn cass_session_execute(
 
     let mut statement = statement_opt.statement.clone();
     let statement_exec_profile = statement_opt.exec_profile.clone();
+    let target_host = statement_opt.target_host;
     #[allow(unused, clippy::let_unit_value)]
     let statement_opt = (); // Hardening shadow to avoid use-after-free.
 
+    let permit = match CassSessionInner::acquire_request_permit_now(session_opt) {
+        Ok(permit) => permit,
+        Err(err) => return CassFuture::make_ready_raw(Err(err)),
+    };
+
     let future = async move {
         let session_guard = session_opt.read().await;
         if session_guard.is_none() {
@@ -262,15 +376,35 @@ pub unsafe extern "C" fn cass_session_execute(
         }
         let cass_session_inner = session_guard.as_ref().unwrap();
         let session = &cass_session_inner.session;
+        let _permit = match permit {
+            Some(permit) => Some(permit),
+            None => cass_session_inner.acquire_request_permit()?,
+        };
 
-        let handle = cass_session_inner
+        let mut handle = cass_session_inner
             .get_or_resolve_profile_handle(statement_exec_profile.as_ref())
             .await?;
+        if let Some(target_host) = target_host {
+            handle = Some(cass_session_inner.target_host_profile_handle(handle, target_host)?);
+        }
 
+        // A timestamp set on the statement takes precedence over the generated one.
+        let timestamp_generator = &cass_session_inner.timestamp_generator;
         match &mut statement {
-            Statement::Simple(query) => query.query.set_execution_profile_handle(handle),
+            Statement::Simple(query) => {
+                query.query.set_execution_profile_handle(handle);
+                if query.query.get_timestamp().is_none() {
+                    query
+                        .query
+                        .set_timestamp(timestamp_generator.next_timestamp());
+                }
+            }
             Statement::Prepared(prepared) => {
-                Arc::make_mut(prepared).set_execution_profile_handle(handle)
+                let prepared = Arc::make_mut(prepared);
+                prepared.set_execution_profile_handle(handle);
+                if prepared.get_timestamp().is_none() {
+                    prepared.set_timestamp(timestamp_generator.next_timestamp());
+                }
             }
         }
 
@@ -534,6 +668,32 @@ pub unsafe extern "C" fn cass_session_close(session: *mut CassSession) -> *const
     })
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn cass_session_reload_ssl(
+    session_raw: *const CassSession,
+    ssl_raw: *const CassSsl,
+) -> CassError {
+    let session_opt = ptr_to_ref(session_raw);
+    let ssl = ptr_to_ref(ssl_raw);
+
+    // The session is locked for writing while it is being connected or closed.
+    let session_guard = match session_opt.try_read() {
+        Ok(session_guard) => session_guard,
+        Err(_) => return CassError::CASS_ERROR_LIB_INVALID_STATE,
+    };
+    match session_guard.as_ref() {
+        Some(CassSessionInner {
+            ssl_context: Some(ssl_context),
+            ..
+        }) => {
+            reload_session_ssl(ssl_context, ssl);
+            CassError::CASS_OK
+        }
+        // Either not connected, or connected without SSL.
+        _ => CassError::CASS_ERROR_LIB_INVALID_STATE,
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn cass_session_get_schema_meta(
     session: *const CassSession,
@@ -620,21 +780,29 @@ mod tests {
     use scylla::{frame::types::LegacyConsistency, transport::errors::DbError};
     use scylla_proxy::{
         Condition, Node, Proxy, Reaction, RequestFrame, RequestOpcode, RequestReaction,
-        RequestRule, ResponseFrame, RunningProxy,
+        RequestRule, ResponseFrame, ResponseOpcode, RunningProxy, TargetShard,
     };
+    use tokio::sync::mpsc;
     use tracing::instrument::WithSubscriber;
 
     use super::*;
     use crate::{
         argconv::{make_c_str, ptr_to_ref},
+        authentication::{
+            cass_authenticator_set_error, CassAuthenticator, CassAuthenticatorCallbacks,
+        },
         batch::{
             cass_batch_add_statement, cass_batch_free, cass_batch_new, cass_batch_set_retry_policy,
         },
         cass_types::CassBatchType,
         cluster::{
-            cass_cluster_free, cass_cluster_new, cass_cluster_set_contact_points_n,
+            cass_cluster_free, cass_cluster_new, cass_cluster_set_authenticator_callbacks,
+            cass_cluster_set_connect_timeout, cass_cluster_set_connection_heartbeat_interval,
+            cass_cluster_set_connection_idle_timeout, cass_cluster_set_contact_points_n,
             cass_cluster_set_execution_profile, cass_cluster_set_latency_aware_routing,
-            cass_cluster_set_retry_policy,
+            cass_cluster_set_load_balance_custom,
+            cass_cluster_set_max_concurrent_requests_threshold, cass_cluster_set_retry_policy,
+            cass_cluster_set_timestamp_gen,
         },
         exec_profile::{
             cass_batch_set_execution_profile, cass_batch_set_execution_profile_n,
@@ -644,18 +812,28 @@ mod tests {
             cass_statement_set_execution_profile_n, ExecProfileName,
         },
         future::{
-            cass_future_error_code, cass_future_error_message, cass_future_free, cass_future_wait,
+            cass_future_error_code, cass_future_error_message, cass_future_free, cass_future_ready,
+            cass_future_wait,
         },
+        inet::CassInet,
+        load_balancing::{CassHostMetadata, CassRoutingInfo},
         retry_policy::{cass_retry_policy_default_new, cass_retry_policy_fallthrough_new},
-        statement::{cass_statement_free, cass_statement_new, cass_statement_set_retry_policy},
+        statement::{
+            cass_statement_free, cass_statement_new, cass_statement_set_host,
+            cass_statement_set_host_inet, cass_statement_set_host_n,
+            cass_statement_set_retry_policy, cass_statement_set_timestamp,
+        },
         testing::assert_cass_error_eq,
-        types::cass_bool_t,
+        timestamp_generator::{cass_timestamp_gen_free, cass_timestamp_gen_server_side_new},
+        types::{cass_bool_t, cass_true},
     };
     use std::{
         collections::HashSet,
         convert::{TryFrom, TryInto},
         iter,
-        net::SocketAddr,
+        net::{IpAddr, SocketAddr},
+        os::raw::c_void,
+        sync::atomic::{AtomicUsize, Ordering},
     };
 
     // This is for convenient logs from failing tests. Just call it at the beginning of a test.
@@ -1315,6 +1493,614 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    #[ntest::timeout(5000)]
+    async fn statement_is_sent_to_target_host() {
+        init_logger();
+        test_with_one_proxy_one(
+            statement_is_sent_to_target_host_do,
+            handshake_rules().into_iter().chain(
+                iter::once(RequestRule(
+                    Condition::RequestOpcode(RequestOpcode::Query).and(
+                        Condition::BodyContainsCaseInsensitive(Box::new(*b"INSERT INTO system.")),
+                    ),
+                    RequestReaction::forge().write_failure(),
+                ))
+                .chain(generic_drop_queries_rules()),
+            ),
+        )
+        .with_current_subscriber()
+        .await;
+    }
+
+    fn statement_is_sent_to_target_host_do(
+        node_addr: SocketAddr,
+        proxy: RunningProxy,
+    ) -> RunningProxy {
+        unsafe {
+            let cluster_raw = cass_cluster_new();
+            let ip = node_addr.ip().to_string();
+            let (c_ip, c_ip_len) = str_to_c_str_n(ip.as_str());
+
+            assert_cass_error_eq!(
+                cass_cluster_set_contact_points_n(cluster_raw, c_ip, c_ip_len),
+                CassError::CASS_OK
+            );
+            let session_raw = cass_session_new();
+            cass_future_wait_check_and_free(cass_session_connect(session_raw, cluster_raw));
+
+            let query = make_c_str!("INSERT INTO system.runtime_info (group, item, value) VALUES ('bindings_test', 'bindings_test', 'bindings_test')");
+            let statement_raw = cass_statement_new(query, 0);
+            {
+                // Invalid hosts
+                assert_cass_error_eq!(
+                    cass_statement_set_host(statement_raw, make_c_str!("not an ip"), 9042),
+                    CassError::CASS_ERROR_LIB_BAD_PARAMS
+                );
+                assert_cass_error_eq!(
+                    cass_statement_set_host(statement_raw, make_c_str!("127.0.0.1"), 65536),
+                    CassError::CASS_ERROR_LIB_BAD_PARAMS
+                );
+                assert!(ptr_to_ref(statement_raw).target_host.is_none());
+            }
+            {
+                // Known host - the request reaches the node
+                assert_cass_error_eq!(
+                    cass_statement_set_host_n(
+                        statement_raw,
+                        c_ip,
+                        c_ip_len,
+                        node_addr.port().into()
+                    ),
+                    CassError::CASS_OK
+                );
+                let fut = cass_session_execute(session_raw, statement_raw);
+                assert_cass_error_eq!(
+                    cass_future_error_code(fut),
+                    CassError::CASS_ERROR_SERVER_WRITE_FAILURE
+                );
+                cass_future_free(fut);
+            }
+            {
+                // Unknown host - the request fails without being sent
+                let unknown_host: CassInet = "127.0.1.231".parse::<IpAddr>().unwrap().into();
+                assert_cass_error_eq!(
+                    cass_statement_set_host_inet(statement_raw, &unknown_host, 9042),
+                    CassError::CASS_OK
+                );
+                let fut = cass_session_execute(session_raw, statement_raw);
+                assert_cass_error_eq!(
+                    cass_future_error_code(fut),
+                    CassError::CASS_ERROR_LIB_NO_HOSTS_AVAILABLE
+                );
+                let mut message: *const c_char = std::ptr::null();
+                let mut message_len: size_t = 0;
+                cass_future_error_message(fut as *mut CassFuture, &mut message, &mut message_len);
+                assert_eq!(
+                    ptr_to_cstr_n(message, message_len),
+                    Some("Host 127.0.1.231:9042 is not known to the driver")
+                );
+                cass_future_free(fut);
+            }
+
+            cass_future_wait_check_and_free(cass_session_close(session_raw));
+            cass_statement_free(statement_raw);
+            cass_session_free(session_raw);
+            cass_cluster_free(cluster_raw);
+        }
+        proxy
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(5000)]
+    async fn requests_over_the_limit_are_rejected() {
+        init_logger();
+        test_with_one_proxy_one(
+            requests_over_the_limit_are_rejected_do,
+            handshake_rules().into_iter().chain(
+                iter::once(RequestRule(
+                    Condition::RequestOpcode(RequestOpcode::Query).and(
+                        Condition::BodyContainsCaseInsensitive(Box::new(*b"INSERT INTO system.")),
+                    ),
+                    RequestReaction::forge()
+                        .write_failure()
+                        .with_delay(Duration::from_millis(1000)),
+                ))
+                .chain(generic_drop_queries_rules()),
+            ),
+        )
+        .with_current_subscriber()
+        .await;
+    }
+
+    fn requests_over_the_limit_are_rejected_do(
+        node_addr: SocketAddr,
+        proxy: RunningProxy,
+    ) -> RunningProxy {
+        unsafe {
+            let cluster_raw = cass_cluster_new();
+            let ip = node_addr.ip().to_string();
+            let (c_ip, c_ip_len) = str_to_c_str_n(ip.as_str());
+
+            assert_cass_error_eq!(
+                cass_cluster_set_contact_points_n(cluster_raw, c_ip, c_ip_len),
+                CassError::CASS_OK
+            );
+            assert_cass_error_eq!(
+                cass_cluster_set_max_concurrent_requests_threshold(cluster_raw, 1),
+                CassError::CASS_OK
+            );
+            let session_raw = cass_session_new();
+            cass_future_wait_check_and_free(cass_session_connect(session_raw, cluster_raw));
+
+            let query = make_c_str!("INSERT INTO system.runtime_info (group, item, value) VALUES ('bindings_test', 'bindings_test', 'bindings_test')");
+            let statement_raw = cass_statement_new(query, 0);
+
+            // The first request is delayed by the node, so it keeps the only slot.
+            let in_flight_fut = cass_session_execute(session_raw, statement_raw);
+            {
+                // The permit is acquired as the request is issued, so the future is ready.
+                let fut = cass_session_execute(session_raw, statement_raw);
+                assert_eq!(cass_future_ready(fut), cass_true);
+                assert_cass_error_eq!(
+                    cass_future_error_code(fut),
+                    CassError::CASS_ERROR_LIB_REQUEST_QUEUE_FULL
+                );
+                cass_future_free(fut);
+            }
+            assert_cass_error_eq!(
+                cass_future_error_code(in_flight_fut),
+                CassError::CASS_ERROR_SERVER_WRITE_FAILURE
+            );
+            cass_future_free(in_flight_fut);
+
+            // The slot is released once the request completes.
+            {
+                let fut = cass_session_execute(session_raw, statement_raw);
+                assert_cass_error_eq!(
+                    cass_future_error_code(fut),
+                    CassError::CASS_ERROR_SERVER_WRITE_FAILURE
+                );
+                cass_future_free(fut);
+            }
+
+            cass_future_wait_check_and_free(cass_session_close(session_raw));
+            cass_statement_free(statement_raw);
+            cass_session_free(session_raw);
+            cass_cluster_free(cluster_raw);
+        }
+        proxy
+    }
+
+    unsafe extern "C" fn counting_plan(
+        _routing_info: *const CassRoutingInfo,
+        _hosts: *const *const CassHostMetadata,
+        hosts_count: size_t,
+        plan: *mut size_t,
+        data: *mut c_void,
+    ) -> size_t {
+        let calls = &*(data as *const AtomicUsize);
+        calls.fetch_add(1, Ordering::SeqCst);
+        for i in 0..hosts_count {
+            *plan.add(i as usize) = i;
+        }
+        hosts_count
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(5000)]
+    async fn custom_policy_is_asked_once_per_query_plan() {
+        init_logger();
+        test_with_one_proxy_one(
+            custom_policy_is_asked_once_per_query_plan_do,
+            handshake_rules().into_iter().chain(
+                iter::once(RequestRule(
+                    Condition::RequestOpcode(RequestOpcode::Query).and(
+                        Condition::BodyContainsCaseInsensitive(Box::new(*b"INSERT INTO system.")),
+                    ),
+                    // The default retry policy moves on to the next node of the plan,
+                    // which makes the driver ask for the fallback plan.
+                    RequestReaction::forge_with_error(DbError::IsBootstrapping),
+                ))
+                .chain(generic_drop_queries_rules()),
+            ),
+        )
+        .with_current_subscriber()
+        .await;
+    }
+
+    fn custom_policy_is_asked_once_per_query_plan_do(
+        node_addr: SocketAddr,
+        proxy: RunningProxy,
+    ) -> RunningProxy {
+        let calls = AtomicUsize::new(0);
+        unsafe {
+            let cluster_raw = cass_cluster_new();
+            let ip = node_addr.ip().to_string();
+            let (c_ip, c_ip_len) = str_to_c_str_n(ip.as_str());
+
+            assert_cass_error_eq!(
+                cass_cluster_set_contact_points_n(cluster_raw, c_ip, c_ip_len),
+                CassError::CASS_OK
+            );
+            assert_cass_error_eq!(
+                cass_cluster_set_load_balance_custom(
+                    cluster_raw,
+                    Some(counting_plan),
+                    None,
+                    &calls as *const AtomicUsize as *mut c_void,
+                ),
+                CassError::CASS_OK
+            );
+            let session_raw = cass_session_new();
+            cass_future_wait_check_and_free(cass_session_connect(session_raw, cluster_raw));
+
+            let query = make_c_str!("INSERT INTO system.runtime_info (group, item, value) VALUES ('bindings_test', 'bindings_test', 'bindings_test')");
+            let statement_raw = cass_statement_new(query, 0);
+            for expected_calls in 1..=2 {
+                let fut = cass_session_execute(session_raw, statement_raw);
+                assert_cass_error_eq!(
+                    cass_future_error_code(fut),
+                    CassError::CASS_ERROR_SERVER_IS_BOOTSTRAPPING
+                );
+                cass_future_free(fut);
+                assert_eq!(calls.load(Ordering::SeqCst), expected_calls);
+            }
+
+            cass_future_wait_check_and_free(cass_session_close(session_raw));
+            cass_statement_free(statement_raw);
+            cass_session_free(session_raw);
+            cass_cluster_free(cluster_raw);
+        }
+        proxy
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(5000)]
+    async fn heartbeats_are_sent_on_idle_connections() {
+        init_logger();
+        let (feedback_tx, feedback_rx) = mpsc::unbounded_channel();
+        test_with_one_proxy_one(
+            move |node_addr, proxy| {
+                heartbeats_are_sent_on_idle_connections_do(node_addr, proxy, feedback_rx)
+            },
+            iter::once(RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Options),
+                RequestReaction::forge_response(Arc::new(move |frame: RequestFrame| {
+                    ResponseFrame::forged_supported(frame.params, &HashMap::new()).unwrap()
+                }))
+                .with_feedback_when_performed(feedback_tx),
+            ))
+            .chain(handshake_rules())
+            .chain(generic_drop_queries_rules()),
+        )
+        .with_current_subscriber()
+        .await;
+    }
+
+    fn heartbeats_are_sent_on_idle_connections_do(
+        node_addr: SocketAddr,
+        proxy: RunningProxy,
+        mut feedback_rx: mpsc::UnboundedReceiver<(RequestFrame, Option<TargetShard>)>,
+    ) -> RunningProxy {
+        unsafe {
+            let cluster_raw = cass_cluster_new();
+            let ip = node_addr.ip().to_string();
+            let (c_ip, c_ip_len) = str_to_c_str_n(ip.as_str());
+
+            assert_cass_error_eq!(
+                cass_cluster_set_contact_points_n(cluster_raw, c_ip, c_ip_len),
+                CassError::CASS_OK
+            );
+            cass_cluster_set_connection_heartbeat_interval(cluster_raw, 1);
+            cass_cluster_set_connection_idle_timeout(cluster_raw, 2);
+
+            let session_raw = cass_session_new();
+            cass_future_wait_check_and_free(cass_session_connect(session_raw, cluster_raw));
+
+            // Skip the OPTIONS requests sent during the handshakes.
+            while feedback_rx.try_recv().is_ok() {}
+
+            // The session stays idle, so every further OPTIONS request is a heartbeat.
+            std::thread::sleep(Duration::from_millis(2500));
+            let mut heartbeats = 0;
+            while feedback_rx.try_recv().is_ok() {
+                heartbeats += 1;
+            }
+            assert!(heartbeats >= 2, "Only {} heartbeats were sent", heartbeats);
+
+            cass_future_wait_check_and_free(cass_session_close(session_raw));
+            cass_session_free(session_raw);
+            cass_cluster_free(cluster_raw);
+        }
+        proxy
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(15000)]
+    async fn connections_are_closed_when_heartbeats_are_not_answered() {
+        init_logger();
+        test_with_one_proxy_one(
+            connections_are_closed_when_heartbeats_are_not_answered_do,
+            handshake_rules()
+                .into_iter()
+                .chain(generic_drop_queries_rules()),
+        )
+        .with_current_subscriber()
+        .await;
+    }
+
+    fn connections_are_closed_when_heartbeats_are_not_answered_do(
+        node_addr: SocketAddr,
+        mut proxy: RunningProxy,
+    ) -> RunningProxy {
+        unsafe {
+            let cluster_raw = cass_cluster_new();
+            let ip = node_addr.ip().to_string();
+            let (c_ip, c_ip_len) = str_to_c_str_n(ip.as_str());
+
+            assert_cass_error_eq!(
+                cass_cluster_set_contact_points_n(cluster_raw, c_ip, c_ip_len),
+                CassError::CASS_OK
+            );
+            cass_cluster_set_connect_timeout(cluster_raw, 500);
+            cass_cluster_set_connection_heartbeat_interval(cluster_raw, 1);
+            cass_cluster_set_connection_idle_timeout(cluster_raw, 2);
+
+            let session_raw = cass_session_new();
+            cass_future_wait_check_and_free(cass_session_connect(session_raw, cluster_raw));
+
+            // Heartbeats are left unanswered from now on.
+            let (heartbeat_tx, mut heartbeat_rx) = mpsc::unbounded_channel();
+            proxy.running_nodes[0].change_request_rules(Some(
+                iter::once(RequestRule(
+                    Condition::RequestOpcode(RequestOpcode::Options),
+                    RequestReaction::drop_frame().with_feedback_when_performed(heartbeat_tx),
+                ))
+                .chain(generic_drop_queries_rules())
+                .collect(),
+            ));
+
+            // Heartbeat interval + idle timeout, with some margin.
+            std::thread::sleep(Duration::from_millis(4000));
+            assert!(
+                heartbeat_rx.try_recv().is_ok(),
+                "No heartbeat was sent on the idle connections"
+            );
+
+            // The unresponsive connections are expected to be closed by now,
+            // so the driver has to open new ones, which requires a new handshake.
+            let (startup_tx, mut startup_rx) = mpsc::unbounded_channel();
+            proxy.running_nodes[0].change_request_rules(Some(
+                iter::once(RequestRule(
+                    Condition::RequestOpcode(RequestOpcode::Startup),
+                    RequestReaction::forge_response(Arc::new(move |frame: RequestFrame| {
+                        ResponseFrame::forged_ready(frame.params)
+                    }))
+                    .with_feedback_when_performed(startup_tx),
+                ))
+                .chain(handshake_rules())
+                .chain(generic_drop_queries_rules())
+                .collect(),
+            ));
+            // A reconnection attempt may still be waiting for its dropped OPTIONS,
+            // so give it time to hit the connect timeout and retry.
+            let reconnected = (0..80).any(|_| {
+                std::thread::sleep(Duration::from_millis(100));
+                startup_rx.try_recv().is_ok()
+            });
+            assert!(reconnected, "Unresponsive connections were not closed");
+
+            cass_future_wait_check_and_free(cass_session_close(session_raw));
+            cass_session_free(session_raw);
+            cass_cluster_free(cluster_raw);
+        }
+        proxy
+    }
+
+    // Reads the timestamp from the body of a QUERY request, if it has one.
+    fn query_timestamp(body: &[u8]) -> Option<i64> {
+        let read_i32 =
+            |offset: usize| i32::from_be_bytes(body[offset..offset + 4].try_into().unwrap());
+        let read_i16 =
+            |offset: usize| i16::from_be_bytes(body[offset..offset + 2].try_into().unwrap());
+
+        // Skip the query string and the consistency.
+        let mut offset = 4 + read_i32(0) as usize + 2;
+        let flags = body[offset];
+        offset += 1;
+        if flags & 0x01 != 0 {
+            let values = read_i16(offset);
+            offset += 2;
+            for _ in 0..values {
+                offset += 4 + read_i32(offset).max(0) as usize;
+            }
+        }
+        if flags & 0x04 != 0 {
+            offset += 4;
+        }
+        if flags & 0x08 != 0 {
+            offset += 4 + read_i32(offset) as usize;
+        }
+        if flags & 0x10 != 0 {
+            offset += 2;
+        }
+        (flags & 0x20 != 0)
+            .then(|| i64::from_be_bytes(body[offset..offset + 8].try_into().unwrap()))
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(5000)]
+    async fn statements_are_timestamped_by_the_generator() {
+        init_logger();
+        let (feedback_tx, feedback_rx) = mpsc::unbounded_channel();
+        test_with_one_proxy_one(
+            move |node_addr, proxy| {
+                statements_are_timestamped_by_the_generator_do(node_addr, proxy, feedback_rx)
+            },
+            handshake_rules().into_iter().chain(
+                iter::once(RequestRule(
+                    Condition::RequestOpcode(RequestOpcode::Query).and(
+                        Condition::BodyContainsCaseInsensitive(Box::new(*b"INSERT INTO system.")),
+                    ),
+                    RequestReaction::forge()
+                        .write_failure()
+                        .with_feedback_when_performed(feedback_tx),
+                ))
+                .chain(generic_drop_queries_rules()),
+            ),
+        )
+        .with_current_subscriber()
+        .await;
+    }
+
+    fn statements_are_timestamped_by_the_generator_do(
+        node_addr: SocketAddr,
+        proxy: RunningProxy,
+        mut feedback_rx: mpsc::UnboundedReceiver<(RequestFrame, Option<TargetShard>)>,
+    ) -> RunningProxy {
+        unsafe {
+            let ip = node_addr.ip().to_string();
+            let (c_ip, c_ip_len) = str_to_c_str_n(ip.as_str());
+            let query = make_c_str!("INSERT INTO system.runtime_info (group, item, value) VALUES ('bindings_test', 'bindings_test', 'bindings_test')");
+
+            let mut execute_and_get_timestamp =
+                |cluster_raw: *mut CassCluster, statement_raw: *const CassStatement| {
+                    let session_raw = cass_session_new();
+                    cass_future_wait_check_and_free(cass_session_connect(session_raw, cluster_raw));
+
+                    let fut = cass_session_execute(session_raw, statement_raw);
+                    assert_cass_error_eq!(
+                        cass_future_error_code(fut),
+                        CassError::CASS_ERROR_SERVER_WRITE_FAILURE
+                    );
+                    cass_future_free(fut);
+
+                    cass_future_wait_check_and_free(cass_session_close(session_raw));
+                    cass_session_free(session_raw);
+                    let (frame, _shard) = feedback_rx.try_recv().unwrap();
+                    query_timestamp(&frame.body)
+                };
+
+            let cluster_raw = cass_cluster_new();
+            assert_cass_error_eq!(
+                cass_cluster_set_contact_points_n(cluster_raw, c_ip, c_ip_len),
+                CassError::CASS_OK
+            );
+            let statement_raw = cass_statement_new(query, 0);
+
+            // Client-side timestamps are generated by default.
+            let first = execute_and_get_timestamp(cluster_raw, statement_raw).unwrap();
+            let second = execute_and_get_timestamp(cluster_raw, statement_raw).unwrap();
+            assert!(first < second);
+
+            // The server assigns the timestamps when so configured.
+            let server_side = cass_timestamp_gen_server_side_new();
+            cass_cluster_set_timestamp_gen(cluster_raw, server_side);
+            cass_timestamp_gen_free(server_side);
+            assert_eq!(execute_and_get_timestamp(cluster_raw, statement_raw), None);
+
+            // A timestamp set on the statement takes precedence over any generator.
+            assert_cass_error_eq!(
+                cass_statement_set_timestamp(statement_raw, 42),
+                CassError::CASS_OK
+            );
+            assert_eq!(
+                execute_and_get_timestamp(cluster_raw, statement_raw),
+                Some(42)
+            );
+
+            cass_statement_free(statement_raw);
+            cass_cluster_free(cluster_raw);
+        }
+        proxy
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(5000)]
+    async fn session_connect_fails_when_authenticator_rejects() {
+        init_logger();
+        test_with_one_proxy_one(
+            session_connect_fails_when_authenticator_rejects_do,
+            [
+                RequestRule(
+                    Condition::RequestOpcode(RequestOpcode::Options),
+                    RequestReaction::forge_response(Arc::new(move |frame: RequestFrame| {
+                        ResponseFrame::forged_supported(frame.params, &HashMap::new()).unwrap()
+                    })),
+                ),
+                RequestRule(
+                    Condition::RequestOpcode(RequestOpcode::Startup),
+                    RequestReaction::forge_response(Arc::new(move |frame: RequestFrame| {
+                        let class_name = b"CustomAuthenticator";
+                        let mut body = (class_name.len() as u16).to_be_bytes().to_vec();
+                        body.extend_from_slice(class_name);
+                        ResponseFrame {
+                            params: frame.params.for_response(),
+                            opcode: ResponseOpcode::Authenticate,
+                            body: body.into(),
+                        }
+                    })),
+                ),
+            ]
+            .into_iter()
+            .chain(generic_drop_queries_rules()),
+        )
+        .with_current_subscriber()
+        .await;
+    }
+
+    unsafe extern "C" fn rejecting_initial_callback(
+        auth: *mut CassAuthenticator,
+        data: *mut c_void,
+    ) {
+        let calls = &*(data as *const AtomicUsize);
+        calls.fetch_add(1, Ordering::SeqCst);
+        cass_authenticator_set_error(auth, make_c_str!("Rejected"));
+    }
+
+    fn session_connect_fails_when_authenticator_rejects_do(
+        node_addr: SocketAddr,
+        proxy: RunningProxy,
+    ) -> RunningProxy {
+        let ip = node_addr.ip().to_string();
+        let (c_ip, c_ip_len) = str_to_c_str_n(ip.as_str());
+        let calls = AtomicUsize::new(0);
+        let callbacks = CassAuthenticatorCallbacks {
+            initial_callback: Some(rejecting_initial_callback),
+            challenge_callback: None,
+            success_callback: None,
+            cleanup_callback: None,
+        };
+
+        unsafe {
+            let cluster_raw = cass_cluster_new();
+            assert_cass_error_eq!(
+                cass_cluster_set_contact_points_n(cluster_raw, c_ip, c_ip_len),
+                CassError::CASS_OK
+            );
+            assert_cass_error_eq!(
+                cass_cluster_set_authenticator_callbacks(
+                    cluster_raw,
+                    &callbacks,
+                    None,
+                    &calls as *const AtomicUsize as *mut c_void,
+                ),
+                CassError::CASS_OK
+            );
+
+            let session_raw = cass_session_new();
+            let connect_fut = cass_session_connect(session_raw, cluster_raw);
+            assert_ne!(cass_future_error_code(connect_fut), CassError::CASS_OK);
+            cass_future_free(connect_fut);
+            assert!(calls.load(Ordering::SeqCst) > 0);
+
+            cass_session_free(session_raw);
+            cass_cluster_free(cluster_raw);
+        }
+        proxy
+    }
+
     rusty_fork_test! {
         #![rusty_fork(timeout_ms = 1000)]
         #[test]
@@ -1,22 +1,46 @@
-use crate::argconv::{clone_arced, free_arced};
+use crate::argconv::{clone_arced, free_arced, ptr_to_cstr_n};
 use crate::cass_error::CassError;
-use crate::types::size_t;
+use crate::types::{cass_byte_t, size_t};
+use foreign_types::{ForeignType, ForeignTypeRef};
+use lazy_static::lazy_static;
 use libc::{c_int, strlen};
-use openssl::ssl::SslVerifyMode;
+use openssl::ex_data::Index;
+use openssl::nid::Nid;
+use openssl::ssl::{SslContext, SslContextBuilder, SslContextRef, SslVerifyMode};
+use openssl::x509::{X509Ref, X509StoreContextRef};
 use openssl_sys::{
-    BIO_free_all, BIO_new_mem_buf, EVP_PKEY_free, PEM_read_bio_PrivateKey, PEM_read_bio_X509,
-    SSL_CTX_add_extra_chain_cert, SSL_CTX_free, SSL_CTX_new, SSL_CTX_set_cert_store,
-    SSL_CTX_set_verify, SSL_CTX_use_PrivateKey, SSL_CTX_use_certificate, TLS_method,
-    X509_STORE_add_cert, X509_STORE_new, X509_free, BIO, SSL_CTX, X509_STORE,
+    d2i_PKCS12, stack_st_X509, BIO_free_all, BIO_new_mem_buf, ERR_clear_error, EVP_PKEY_cmp,
+    EVP_PKEY_free, EVP_PKEY_up_ref, OPENSSL_sk_new_null, OPENSSL_sk_num, OPENSSL_sk_pop_free,
+    OPENSSL_sk_push, OPENSSL_sk_value, PEM_read_bio_PrivateKey, PEM_read_bio_X509, PKCS12_free,
+    PKCS12_parse, SSL_CTX_add_extra_chain_cert, SSL_CTX_ctrl, SSL_CTX_free,
+    SSL_CTX_get0_certificate, SSL_CTX_get0_privatekey, SSL_CTX_get_extra_chain_certs,
+    SSL_CTX_get_max_proto_version, SSL_CTX_get_min_proto_version, SSL_CTX_get_verify_mode,
+    SSL_CTX_new, SSL_CTX_set_cert_store, SSL_CTX_set_cipher_list, SSL_CTX_set_ciphersuites,
+    SSL_CTX_set_max_proto_version, SSL_CTX_set_min_proto_version, SSL_CTX_set_verify,
+    SSL_CTX_use_PrivateKey, SSL_CTX_use_certificate, SSL_ctrl, TLS_method, X509_OBJECT_get0_X509,
+    X509_STORE_add_cert, X509_STORE_get0_objects, X509_STORE_new, X509_STORE_set_default_paths,
+    X509_free, X509_get_pubkey, X509_up_ref, X509_verify_cert, BIO, EVP_PKEY, OPENSSL_STACK, SSL,
+    SSL_CTX, TLS1_1_VERSION, TLS1_2_VERSION, TLS1_3_VERSION, TLS1_VERSION, X509, X509_OBJECT,
+    X509_STORE, X509_STORE_CTX,
 };
 use std::convert::TryInto;
+use std::ffi::CString;
+use std::mem::ManuallyDrop;
+use std::net::IpAddr;
 use std::os::raw::c_char;
 use std::os::raw::c_void;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+type StringOptionSetter = unsafe fn(*mut SSL_CTX, *const c_char) -> c_int;
 
 pub struct CassSsl {
     pub(crate) ssl_context: *mut SSL_CTX,
     pub(crate) trusted_store: *mut X509_STORE,
+    pub(crate) peer_identity_verifier: Arc<PeerIdentityVerifier>,
+    // OpenSSL cannot read back some of the options, e.g. the groups, so they are
+    // kept to be applied to the contexts of the sessions, see `session_context()`.
+    string_options: Mutex<Vec<(StringOptionSetter, CString)>>,
 }
 
 pub const CASS_SSL_VERIFY_NONE: i32 = 0x00;
@@ -24,6 +48,45 @@ pub const CASS_SSL_VERIFY_PEER_CERT: i32 = 0x01;
 pub const CASS_SSL_VERIFY_PEER_IDENTITY: i32 = 0x02;
 pub const CASS_SSL_VERIFY_PEER_IDENTITY_DNS: i32 = 0x04;
 
+pub const CASS_SSL_TLS_VERSION_DEFAULT: i32 = 0x00;
+pub const CASS_SSL_TLS_VERSION_1_0: i32 = 0x01;
+pub const CASS_SSL_TLS_VERSION_1_1: i32 = 0x02;
+pub const CASS_SSL_TLS_VERSION_1_2: i32 = 0x03;
+pub const CASS_SSL_TLS_VERSION_1_3: i32 = 0x04;
+
+// Not exported by openssl-sys, see `SSL_CTX_set1_groups_list`
+// and `SSL_add1_chain_cert` in ssl.h.
+const SSL_CTRL_SET_GROUPS_LIST: c_int = 92;
+const SSL_CTRL_CHAIN_CERT: c_int = 89;
+const SSL_CTRL_CLEAR_EXTRA_CHAIN_CERTS: c_int = 83;
+
+extern "C" {
+    fn SSL_CTX_set_cert_cb(
+        ctx: *mut SSL_CTX,
+        cb: Option<unsafe extern "C" fn(*mut SSL, *mut c_void) -> c_int>,
+        arg: *mut c_void,
+    );
+    fn SSL_CTX_set_cert_verify_callback(
+        ctx: *mut SSL_CTX,
+        cb: Option<unsafe extern "C" fn(*mut X509_STORE_CTX, *mut c_void) -> c_int>,
+        arg: *mut c_void,
+    );
+    fn X509_STORE_CTX_set0_trusted_stack(ctx: *mut X509_STORE_CTX, sk: *mut stack_st_X509);
+    fn SSL_use_certificate(ssl: *mut SSL, x: *mut X509) -> c_int;
+    fn SSL_use_PrivateKey(ssl: *mut SSL, pkey: *mut EVP_PKEY) -> c_int;
+    fn OPENSSL_sk_shift(st: *mut OPENSSL_STACK) -> *mut c_void;
+    fn X509_STORE_up_ref(store: *mut X509_STORE) -> c_int;
+}
+
+lazy_static! {
+    // Keeps the reload state alive for as long as the `SSL_CTX` of the session,
+    // which is shared with the Rust driver and may outlive the session.
+    static ref RELOAD_STATE_INDEX: Index<SslContext, Arc<SslReloadState>> =
+        SslContext::new_ex_index().unwrap();
+    // Serializes the first reloads, which install the reload callbacks.
+    static ref RELOAD_CALLBACKS_LOCK: Mutex<()> = Mutex::new(());
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn cass_ssl_new() -> *const CassSsl {
     openssl_sys::init();
@@ -41,11 +104,52 @@ pub unsafe extern "C" fn cass_ssl_new_no_lib_init() -> *const CassSsl {
     let ssl = CassSsl {
         ssl_context,
         trusted_store,
+        peer_identity_verifier: Default::default(),
+        string_options: Default::default(),
     };
 
     Arc::into_raw(Arc::new(ssl)) as *const CassSsl
 }
 
+impl CassSsl {
+    // Every session is given a copy of the context, so that the TLS material
+    // reloaded on a session does not affect the other sessions. The trusted
+    // store is shared, like the context used to be.
+    pub(crate) unsafe fn session_context(&self) -> SslContext {
+        let ssl_context = SSL_CTX_new(TLS_method());
+        X509_STORE_up_ref(self.trusted_store);
+        SSL_CTX_set_cert_store(ssl_context, self.trusted_store);
+        SSL_CTX_set_verify(ssl_context, SSL_CTX_get_verify_mode(self.ssl_context), None);
+        SSL_CTX_set_min_proto_version(ssl_context, SSL_CTX_get_min_proto_version(self.ssl_context));
+        SSL_CTX_set_max_proto_version(ssl_context, SSL_CTX_get_max_proto_version(self.ssl_context));
+        for (set_option, value) in self.string_options.lock().unwrap().iter() {
+            set_option(ssl_context, value.as_ptr());
+        }
+
+        let cert = SSL_CTX_get0_certificate(self.ssl_context);
+        let key = SSL_CTX_get0_privatekey(self.ssl_context);
+        if !cert.is_null() {
+            SSL_CTX_use_certificate(ssl_context, cert);
+        }
+        if !key.is_null() {
+            SSL_CTX_use_PrivateKey(ssl_context, key);
+        }
+        let mut extra_chain_certs: *mut stack_st_X509 = std::ptr::null_mut();
+        SSL_CTX_get_extra_chain_certs(self.ssl_context, &mut extra_chain_certs);
+        if !extra_chain_certs.is_null() {
+            let extra_chain_certs = extra_chain_certs as *const OPENSSL_STACK;
+            for i in 0..OPENSSL_sk_num(extra_chain_certs) {
+                // The context takes ownership of the certificate.
+                let x509 = OPENSSL_sk_value(extra_chain_certs, i) as *mut X509;
+                X509_up_ref(x509);
+                SSL_CTX_add_extra_chain_cert(ssl_context, x509);
+            }
+        }
+
+        SslContext::from_ptr(ssl_context)
+    }
+}
+
 impl Drop for CassSsl {
     fn drop(&mut self) {
         unsafe {
@@ -110,48 +214,769 @@ pub unsafe extern "C" fn cass_ssl_add_trusted_cert_n(
         return CassError::CASS_ERROR_SSL_INVALID_CERT;
     }
 
-    let x509 = PEM_read_bio_X509(
-        bio,
-        std::ptr::null_mut(),
-        Some(pem_password_callback),
+    // The PEM data may contain a whole chain of certificates.
+    let mut added_certs = 0;
+    loop {
+        let x509 = PEM_read_bio_X509(
+            bio,
+            std::ptr::null_mut(),
+            Some(pem_password_callback),
+            std::ptr::null_mut(),
+        );
+
+        if x509.is_null() {
+            break;
+        }
+
+        let rc = X509_STORE_add_cert(ssl.trusted_store, x509);
+        X509_free(x509);
+        if rc != 1 {
+            BIO_free_all(bio);
+            ERR_clear_error();
+            return CassError::CASS_ERROR_SSL_INVALID_CERT;
+        }
+        added_certs += 1;
+    }
+
+    BIO_free_all(bio);
+    // Reading past the last certificate leaves an error in the queue.
+    ERR_clear_error();
+
+    if added_certs == 0 {
+        return CassError::CASS_ERROR_SSL_INVALID_CERT;
+    }
+
+    CassError::CASS_OK
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_set_verify_flags(ssl: *mut CassSsl, flags: i32) {
+    let ssl = clone_arced(ssl);
+    ssl.peer_identity_verifier
+        .flags
+        .store(flags, Ordering::Relaxed);
+
+    // Identities are verified for each connection, see `PeerIdentityVerifier`.
+    let mode = match flags {
+        CASS_SSL_VERIFY_NONE => SslVerifyMode::NONE,
+        _ => SslVerifyMode::PEER,
+    };
+    SSL_CTX_set_verify(ssl.ssl_context, mode.bits(), None);
+}
+
+// A file which cannot be read is reported like invalid contents of the file.
+unsafe fn read_file(
+    path: *const c_char,
+    path_length: size_t,
+    read_error: CassError,
+) -> Result<Vec<u8>, CassError> {
+    let path = ptr_to_cstr_n(path, path_length).ok_or(CassError::CASS_ERROR_LIB_BAD_PARAMS)?;
+    std::fs::read(path).map_err(|err| {
+        tracing::error!("Unable to read {}: {}", path, err);
+        read_error
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_add_trusted_cert_file(
+    ssl: *mut CassSsl,
+    path: *const c_char,
+) -> CassError {
+    if path.is_null() {
+        return CassError::CASS_ERROR_LIB_BAD_PARAMS;
+    }
+
+    cass_ssl_add_trusted_cert_file_n(ssl, path, strlen(path).try_into().unwrap())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_add_trusted_cert_file_n(
+    ssl: *mut CassSsl,
+    path: *const c_char,
+    path_length: size_t,
+) -> CassError {
+    match read_file(path, path_length, CassError::CASS_ERROR_SSL_INVALID_CERT) {
+        Ok(cert) => cass_ssl_add_trusted_cert_n(
+            ssl,
+            cert.as_ptr() as *const c_char,
+            cert.len().try_into().unwrap(),
+        ),
+        Err(err) => err,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_add_system_trusted_certs(ssl: *mut CassSsl) -> CassError {
+    let ssl = clone_arced(ssl);
+
+    if X509_STORE_set_default_paths(ssl.trusted_store) != 1 {
+        return CassError::CASS_ERROR_SSL_INVALID_CERT;
+    }
+
+    CassError::CASS_OK
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_set_cert_file(
+    ssl: *mut CassSsl,
+    path: *const c_char,
+) -> CassError {
+    if path.is_null() {
+        return CassError::CASS_ERROR_LIB_BAD_PARAMS;
+    }
+
+    cass_ssl_set_cert_file_n(ssl, path, strlen(path).try_into().unwrap())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_set_cert_file_n(
+    ssl: *mut CassSsl,
+    path: *const c_char,
+    path_length: size_t,
+) -> CassError {
+    match read_file(path, path_length, CassError::CASS_ERROR_SSL_INVALID_CERT) {
+        Ok(cert) => cass_ssl_set_cert_n(
+            ssl,
+            cert.as_ptr() as *const c_char,
+            cert.len().try_into().unwrap(),
+        ),
+        Err(err) => err,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_set_private_key_file(
+    ssl: *mut CassSsl,
+    path: *const c_char,
+    password: *mut c_char,
+) -> CassError {
+    if path.is_null() {
+        return CassError::CASS_ERROR_LIB_BAD_PARAMS;
+    }
+    if password.is_null() {
+        return CassError::CASS_ERROR_SSL_INVALID_PRIVATE_KEY;
+    }
+
+    cass_ssl_set_private_key_file_n(
+        ssl,
+        path,
+        strlen(path).try_into().unwrap(),
+        password,
+        strlen(password).try_into().unwrap(),
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_set_private_key_file_n(
+    ssl: *mut CassSsl,
+    path: *const c_char,
+    path_length: size_t,
+    password: *mut c_char,
+    password_length: size_t,
+) -> CassError {
+    match read_file(
+        path,
+        path_length,
+        CassError::CASS_ERROR_SSL_INVALID_PRIVATE_KEY,
+    ) {
+        Ok(key) => cass_ssl_set_private_key_n(
+            ssl,
+            key.as_ptr() as *const c_char,
+            key.len().try_into().unwrap(),
+            password,
+            password_length,
+        ),
+        Err(err) => err,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_set_pkcs12(
+    ssl: *mut CassSsl,
+    data: *const cass_byte_t,
+    data_size: size_t,
+    password: *const c_char,
+) -> CassError {
+    if password.is_null() {
+        return CassError::CASS_ERROR_SSL_INVALID_PRIVATE_KEY;
+    }
+
+    cass_ssl_set_pkcs12_n(
+        ssl,
+        data,
+        data_size,
+        password,
+        strlen(password).try_into().unwrap(),
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_set_pkcs12_n(
+    ssl: *mut CassSsl,
+    data: *const cass_byte_t,
+    data_size: size_t,
+    password: *const c_char,
+    password_length: size_t,
+) -> CassError {
+    let ssl = clone_arced(ssl);
+    let password = match ptr_to_cstr_n(password, password_length).and_then(|p| CString::new(p).ok())
+    {
+        Some(password) => password,
+        None => return CassError::CASS_ERROR_SSL_INVALID_PRIVATE_KEY,
+    };
+
+    let mut der = data;
+    let p12 = d2i_PKCS12(
         std::ptr::null_mut(),
+        &mut der,
+        data_size.try_into().unwrap(),
     );
+    if p12.is_null() {
+        ERR_clear_error();
+        return CassError::CASS_ERROR_SSL_INVALID_CERT;
+    }
 
-    BIO_free_all(bio);
+    let mut pkey: *mut EVP_PKEY = std::ptr::null_mut();
+    let mut cert: *mut X509 = std::ptr::null_mut();
+    let mut chain: *mut stack_st_X509 = std::ptr::null_mut();
+    let rc = PKCS12_parse(p12, password.as_ptr(), &mut pkey, &mut cert, &mut chain);
+    PKCS12_free(p12);
+
+    if rc != 1 {
+        ERR_clear_error();
+        return CassError::CASS_ERROR_SSL_INVALID_PRIVATE_KEY;
+    }
+
+    let result = set_pkcs12_material(ssl.ssl_context, cert, pkey, chain);
+    ERR_clear_error();
+
+    // The context holds its own references to the certificate and the key,
+    // and the chain certificates still in the stack were not handed over to it.
+    if !chain.is_null() {
+        OPENSSL_sk_pop_free(chain as *mut OPENSSL_STACK, Some(free_x509));
+    }
+    if !cert.is_null() {
+        X509_free(cert);
+    }
+    if !pkey.is_null() {
+        EVP_PKEY_free(pkey);
+    }
+
+    result
+}
 
-    if x509.is_null() {
+// The bundle is validated before anything is applied, so that a rejected bundle
+// leaves the context as it was. The chain replaces the extra chain of the context.
+unsafe fn set_pkcs12_material(
+    ssl_context: *mut SSL_CTX,
+    cert: *mut X509,
+    pkey: *mut EVP_PKEY,
+    chain: *mut stack_st_X509,
+) -> CassError {
+    if cert.is_null() {
         return CassError::CASS_ERROR_SSL_INVALID_CERT;
     }
+    if pkey.is_null() {
+        return CassError::CASS_ERROR_SSL_INVALID_PRIVATE_KEY;
+    }
+    let cert_pkey = X509_get_pubkey(cert);
+    let key_matches = !cert_pkey.is_null() && EVP_PKEY_cmp(cert_pkey, pkey) == 1;
+    if !cert_pkey.is_null() {
+        EVP_PKEY_free(cert_pkey);
+    }
+    if !key_matches {
+        return CassError::CASS_ERROR_SSL_INVALID_PRIVATE_KEY;
+    }
 
-    X509_STORE_add_cert(ssl.trusted_store, x509);
-    X509_free(x509);
+    if SSL_CTX_use_certificate(ssl_context, cert) != 1 {
+        return CassError::CASS_ERROR_SSL_INVALID_CERT;
+    }
+    if SSL_CTX_use_PrivateKey(ssl_context, pkey) != 1 {
+        return CassError::CASS_ERROR_SSL_INVALID_PRIVATE_KEY;
+    }
+
+    SSL_CTX_ctrl(
+        ssl_context,
+        SSL_CTRL_CLEAR_EXTRA_CHAIN_CERTS,
+        0,
+        std::ptr::null_mut(),
+    );
+    if !chain.is_null() {
+        let chain = chain as *mut OPENSSL_STACK;
+        while OPENSSL_sk_num(chain) > 0 {
+            // The context takes the ownership of the certificate only on success,
+            // otherwise it is freed along with the rest of the stack.
+            let x509 = OPENSSL_sk_value(chain, 0) as *mut X509;
+            if SSL_CTX_add_extra_chain_cert(ssl_context, x509) == 0 {
+                return CassError::CASS_ERROR_SSL_INVALID_CERT;
+            }
+            OPENSSL_sk_shift(chain);
+        }
+    }
 
     CassError::CASS_OK
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn cass_ssl_set_verify_flags(ssl: *mut CassSsl, flags: i32) {
+pub unsafe extern "C" fn cass_ssl_set_pkcs12_file(
+    ssl: *mut CassSsl,
+    path: *const c_char,
+    password: *const c_char,
+) -> CassError {
+    if path.is_null() {
+        return CassError::CASS_ERROR_LIB_BAD_PARAMS;
+    }
+    if password.is_null() {
+        return CassError::CASS_ERROR_SSL_INVALID_PRIVATE_KEY;
+    }
+
+    cass_ssl_set_pkcs12_file_n(
+        ssl,
+        path,
+        strlen(path).try_into().unwrap(),
+        password,
+        strlen(password).try_into().unwrap(),
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_set_pkcs12_file_n(
+    ssl: *mut CassSsl,
+    path: *const c_char,
+    path_length: size_t,
+    password: *const c_char,
+    password_length: size_t,
+) -> CassError {
+    match read_file(path, path_length, CassError::CASS_ERROR_SSL_INVALID_CERT) {
+        Ok(data) => cass_ssl_set_pkcs12_n(
+            ssl,
+            data.as_ptr(),
+            data.len().try_into().unwrap(),
+            password,
+            password_length,
+        ),
+        Err(err) => err,
+    }
+}
+
+fn tls_version_to_openssl(version: i32) -> Option<c_int> {
+    match version {
+        // Zero lets OpenSSL use the lowest/highest version it supports.
+        CASS_SSL_TLS_VERSION_DEFAULT => Some(0),
+        CASS_SSL_TLS_VERSION_1_0 => Some(TLS1_VERSION),
+        CASS_SSL_TLS_VERSION_1_1 => Some(TLS1_1_VERSION),
+        CASS_SSL_TLS_VERSION_1_2 => Some(TLS1_2_VERSION),
+        CASS_SSL_TLS_VERSION_1_3 => Some(TLS1_3_VERSION),
+        _ => None,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_set_min_protocol_version(
+    ssl: *mut CassSsl,
+    version: i32,
+) -> CassError {
     let ssl = clone_arced(ssl);
+    match tls_version_to_openssl(version) {
+        Some(version) if SSL_CTX_set_min_proto_version(ssl.ssl_context, version) == 1 => {
+            CassError::CASS_OK
+        }
+        _ => CassError::CASS_ERROR_SSL_PROTOCOL_ERROR,
+    }
+}
 
-    match flags {
-        CASS_SSL_VERIFY_NONE => {
-            SSL_CTX_set_verify(ssl.ssl_context, SslVerifyMode::NONE.bits(), None)
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_set_max_protocol_version(
+    ssl: *mut CassSsl,
+    version: i32,
+) -> CassError {
+    let ssl = clone_arced(ssl);
+    match tls_version_to_openssl(version) {
+        Some(version) if SSL_CTX_set_max_proto_version(ssl.ssl_context, version) == 1 => {
+            CassError::CASS_OK
         }
-        CASS_SSL_VERIFY_PEER_CERT => {
-            SSL_CTX_set_verify(ssl.ssl_context, SslVerifyMode::PEER.bits(), None)
+        _ => CassError::CASS_ERROR_SSL_PROTOCOL_ERROR,
+    }
+}
+
+unsafe fn ssl_set_string_option(
+    ssl: *mut CassSsl,
+    value: *const c_char,
+    value_length: size_t,
+    set_option: StringOptionSetter,
+) -> CassError {
+    let ssl = clone_arced(ssl);
+    // OpenSSL expects a null-terminated string.
+    let value = match ptr_to_cstr_n(value, value_length).and_then(|v| CString::new(v).ok()) {
+        Some(value) => value,
+        None => return CassError::CASS_ERROR_SSL_PROTOCOL_ERROR,
+    };
+
+    if set_option(ssl.ssl_context, value.as_ptr()) != 1 {
+        return CassError::CASS_ERROR_SSL_PROTOCOL_ERROR;
+    }
+    ssl.string_options.lock().unwrap().push((set_option, value));
+
+    CassError::CASS_OK
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_set_cipher_list(
+    ssl: *mut CassSsl,
+    cipher_list: *const c_char,
+) -> CassError {
+    if cipher_list.is_null() {
+        return CassError::CASS_ERROR_SSL_PROTOCOL_ERROR;
+    }
+
+    cass_ssl_set_cipher_list_n(ssl, cipher_list, strlen(cipher_list).try_into().unwrap())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_set_cipher_list_n(
+    ssl: *mut CassSsl,
+    cipher_list: *const c_char,
+    cipher_list_length: size_t,
+) -> CassError {
+    ssl_set_string_option(ssl, cipher_list, cipher_list_length, |ctx, value| {
+        SSL_CTX_set_cipher_list(ctx, value)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_set_ciphersuites(
+    ssl: *mut CassSsl,
+    ciphersuites: *const c_char,
+) -> CassError {
+    if ciphersuites.is_null() {
+        return CassError::CASS_ERROR_SSL_PROTOCOL_ERROR;
+    }
+
+    cass_ssl_set_ciphersuites_n(ssl, ciphersuites, strlen(ciphersuites).try_into().unwrap())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_set_ciphersuites_n(
+    ssl: *mut CassSsl,
+    ciphersuites: *const c_char,
+    ciphersuites_length: size_t,
+) -> CassError {
+    ssl_set_string_option(ssl, ciphersuites, ciphersuites_length, |ctx, value| {
+        SSL_CTX_set_ciphersuites(ctx, value)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_set_groups(
+    ssl: *mut CassSsl,
+    groups: *const c_char,
+) -> CassError {
+    if groups.is_null() {
+        return CassError::CASS_ERROR_SSL_PROTOCOL_ERROR;
+    }
+
+    cass_ssl_set_groups_n(ssl, groups, strlen(groups).try_into().unwrap())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_ssl_set_groups_n(
+    ssl: *mut CassSsl,
+    groups: *const c_char,
+    groups_length: size_t,
+) -> CassError {
+    ssl_set_string_option(ssl, groups, groups_length, |ctx, value| {
+        SSL_CTX_ctrl(ctx, SSL_CTRL_SET_GROUPS_LIST, 0, value as *mut c_void) as c_int
+    })
+}
+
+// TLS material swapped in on a connected session, see `cass_session_reload_ssl()`.
+//
+// The `SSL_CTX` of the session is shared with the Rust driver and cannot be safely
+// modified while connections are being opened. Instead, the reloaded material is
+// applied to each new connection from the OpenSSL callbacks installed on the context
+// by the first reload, so existing connections are left intact.
+#[derive(Default)]
+struct SslReloadState {
+    material: RwLock<Option<SslMaterial>>,
+}
+
+// Replaces the TLS material of the session using `ssl_context` with the one of `ssl`.
+pub(crate) fn reload_session_ssl(ssl_context: &SslContextRef, ssl: &CassSsl) {
+    let material = unsafe { SslMaterial::from_ssl(ssl) };
+    let state = match ssl_context.ex_data(*RELOAD_STATE_INDEX) {
+        Some(state) => state.clone(),
+        None => unsafe { install_reload_callbacks(ssl_context) },
+    };
+    *state.material.write().unwrap() = Some(material);
+}
+
+unsafe fn install_reload_callbacks(ssl_context: &SslContextRef) -> Arc<SslReloadState> {
+    let _guard = RELOAD_CALLBACKS_LOCK.lock().unwrap();
+    if let Some(state) = ssl_context.ex_data(*RELOAD_STATE_INDEX) {
+        return state.clone();
+    }
+
+    let state: Arc<SslReloadState> = Default::default();
+    // The builder does not own the context, it is only borrowed to attach the state.
+    ManuallyDrop::new(SslContextBuilder::from_ptr(ssl_context.as_ptr()))
+        .set_ex_data(*RELOAD_STATE_INDEX, state.clone());
+    let state_ptr = Arc::as_ptr(&state) as *mut c_void;
+    SSL_CTX_set_cert_cb(
+        ssl_context.as_ptr(),
+        Some(reloaded_cert_callback),
+        state_ptr,
+    );
+    SSL_CTX_set_cert_verify_callback(
+        ssl_context.as_ptr(),
+        Some(reloaded_cert_verify_callback),
+        state_ptr,
+    );
+    state
+}
+
+struct SslMaterial {
+    trusted_certs: *mut stack_st_X509,
+    cert: *mut X509,
+    chain: Vec<*mut X509>,
+    key: *mut EVP_PKEY,
+}
+
+// The material is never modified after it is created, and OpenSSL
+// reference counting of its certificates and keys is thread safe.
+unsafe impl Send for SslMaterial {}
+unsafe impl Sync for SslMaterial {}
+
+unsafe extern "C" fn free_x509(x509: *mut c_void) {
+    X509_free(x509 as *mut X509);
+}
+
+impl SslMaterial {
+    unsafe fn from_ssl(ssl: &CassSsl) -> Self {
+        let trusted_certs = OPENSSL_sk_new_null();
+        let objects = X509_STORE_get0_objects(ssl.trusted_store) as *const OPENSSL_STACK;
+        for i in 0..OPENSSL_sk_num(objects) {
+            let x509 = X509_OBJECT_get0_X509(OPENSSL_sk_value(objects, i) as *const X509_OBJECT);
+            if !x509.is_null() {
+                X509_up_ref(x509);
+                OPENSSL_sk_push(trusted_certs, x509 as *const c_void);
+            }
         }
-        _ => {
-            if flags & CASS_SSL_VERIFY_PEER_IDENTITY != 0 {
-                eprintln!("The CASS_SSL_VERIFY_PEER_CERT_IDENTITY is not supported, CASS_SSL_VERIFY_PEER_CERT is set in SSL context.");
+
+        let cert = SSL_CTX_get0_certificate(ssl.ssl_context);
+        let key = SSL_CTX_get0_privatekey(ssl.ssl_context);
+        let mut chain = Vec::new();
+        if !cert.is_null() && !key.is_null() {
+            X509_up_ref(cert);
+            EVP_PKEY_up_ref(key);
+
+            let mut extra_chain_certs: *mut stack_st_X509 = std::ptr::null_mut();
+            SSL_CTX_get_extra_chain_certs(ssl.ssl_context, &mut extra_chain_certs);
+            if !extra_chain_certs.is_null() {
+                let extra_chain_certs = extra_chain_certs as *const OPENSSL_STACK;
+                for i in 0..OPENSSL_sk_num(extra_chain_certs) {
+                    let x509 = OPENSSL_sk_value(extra_chain_certs, i) as *mut X509;
+                    X509_up_ref(x509);
+                    chain.push(x509);
+                }
             }
+        }
+
+        SslMaterial {
+            trusted_certs: trusted_certs as *mut stack_st_X509,
+            cert: if key.is_null() {
+                std::ptr::null_mut()
+            } else {
+                cert
+            },
+            chain,
+            key: if cert.is_null() {
+                std::ptr::null_mut()
+            } else {
+                key
+            },
+        }
+    }
+}
 
-            if flags & CASS_SSL_VERIFY_PEER_IDENTITY_DNS != 0 {
-                eprintln!("The CASS_SSL_VERIFY_PEER_CERT_IDENTITY_DNS is not supported, CASS_SSL_VERIFY_PEER_CERT is set in SSL context.");
+impl Drop for SslMaterial {
+    fn drop(&mut self) {
+        unsafe {
+            OPENSSL_sk_pop_free(self.trusted_certs as *mut OPENSSL_STACK, Some(free_x509));
+            if !self.cert.is_null() {
+                X509_free(self.cert);
+                EVP_PKEY_free(self.key);
             }
+            self.chain.iter().for_each(|x509| X509_free(*x509));
+        }
+    }
+}
+
+// Replaces the client certificate and key of a new connection with the reloaded ones.
+unsafe extern "C" fn reloaded_cert_callback(ssl: *mut SSL, arg: *mut c_void) -> c_int {
+    let state = &*(arg as *const SslReloadState);
+    let material = state.material.read().unwrap();
+    let material = match material.as_ref() {
+        Some(material) if !material.cert.is_null() => material,
+        _ => return 1,
+    };
 
-            SSL_CTX_set_verify(ssl.ssl_context, SslVerifyMode::PEER.bits(), None);
+    if SSL_use_certificate(ssl, material.cert) != 1 || SSL_use_PrivateKey(ssl, material.key) != 1 {
+        return 0;
+    }
+    for x509 in &material.chain {
+        if SSL_ctrl(ssl, SSL_CTRL_CHAIN_CERT, 1, *x509 as *mut c_void) != 1 {
+            return 0;
         }
     }
+
+    1
+}
+
+// Verifies the certificate chain of a new connection against the reloaded trusted certificates.
+unsafe extern "C" fn reloaded_cert_verify_callback(
+    store_ctx: *mut X509_STORE_CTX,
+    arg: *mut c_void,
+) -> c_int {
+    let state = &*(arg as *const SslReloadState);
+    // The read lock keeps the trusted certificates alive until the verification is done.
+    let material = state.material.read().unwrap();
+    if let Some(material) = material.as_ref() {
+        X509_STORE_CTX_set0_trusted_stack(store_ctx, material.trusted_certs);
+    }
+
+    X509_verify_cert(store_ctx)
+}
+
+// Checks the identity of the nodes for CASS_SSL_VERIFY_PEER_IDENTITY and
+// CASS_SSL_VERIFY_PEER_IDENTITY_DNS.
+//
+// The Rust driver performs the TLS handshake itself and does not tell which node
+// it is performed with, so the connections of a session verifying identities go
+// through `TlsTunnels`, which check the certificate of each node against that node.
+#[derive(Default)]
+pub(crate) struct PeerIdentityVerifier {
+    flags: AtomicI32,
+}
+
+impl PeerIdentityVerifier {
+    #[cfg(test)]
+    pub(crate) fn new(flags: i32) -> Self {
+        Self {
+            flags: AtomicI32::new(flags),
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.flags.load(Ordering::Relaxed)
+            & (CASS_SSL_VERIFY_PEER_IDENTITY | CASS_SSL_VERIFY_PEER_IDENTITY_DNS)
+            != 0
+    }
+
+    // Whether the reverse-resolved hostname of a node is needed to verify it.
+    pub(crate) fn verifies_hostname(&self) -> bool {
+        self.flags.load(Ordering::Relaxed) & CASS_SSL_VERIFY_PEER_IDENTITY_DNS != 0
+    }
+
+    // Verifies the certificate chain presented by the node at `address`, whose
+    // hostname is `None` if it is not needed, see `verifies_hostname()`.
+    pub(crate) fn verify(
+        &self,
+        ctx: &mut X509StoreContextRef,
+        address: IpAddr,
+        hostname: Option<&str>,
+    ) -> bool {
+        // Only the leaf certificate identifies the node, the rest of the chain
+        // was already checked by OpenSSL.
+        if ctx.error_depth() != 0 {
+            return true;
+        }
+        match ctx.current_cert() {
+            Some(cert) => self.verify_cert(cert, address, hostname),
+            None => false,
+        }
+    }
+
+    // Both identities are checked if both flags are set.
+    fn verify_cert(&self, cert: &X509Ref, address: IpAddr, hostname: Option<&str>) -> bool {
+        let flags = self.flags.load(Ordering::Relaxed);
+        let address_verified =
+            flags & CASS_SSL_VERIFY_PEER_IDENTITY == 0 || verify_ip_address(cert, address);
+        let hostname_verified = flags & CASS_SSL_VERIFY_PEER_IDENTITY_DNS == 0
+            || hostname.map_or(false, |hostname| verify_hostname(cert, hostname));
+
+        let verified = address_verified && hostname_verified;
+        if !verified {
+            tracing::error!(
+                "Peer certificate of node {} ({}) does not match its identity: {:?}",
+                address,
+                hostname.unwrap_or("hostname not resolved"),
+                cert.subject_name()
+            );
+        }
+        verified
+    }
+}
+
+fn common_names(cert: &X509Ref) -> impl Iterator<Item = String> + '_ {
+    cert.subject_name()
+        .entries_by_nid(Nid::COMMONNAME)
+        .filter_map(|entry| entry.data().as_utf8().ok())
+        .map(|name| name.to_string())
+}
+
+fn verify_ip_address(cert: &X509Ref, address: IpAddr) -> bool {
+    let octets = match address {
+        IpAddr::V4(address) => address.octets().to_vec(),
+        IpAddr::V6(address) => address.octets().to_vec(),
+    };
+
+    // Common name is only consulted if there are no IP addresses in SAN.
+    let san_addresses: Vec<&[u8]> = cert
+        .subject_alt_names()
+        .map(|names| names.iter().filter_map(|name| name.ipaddress()).collect())
+        .unwrap_or_default();
+    if !san_addresses.is_empty() {
+        return san_addresses.iter().any(|san| *san == octets.as_slice());
+    }
+
+    common_names(cert).any(|name| name.parse::<IpAddr>() == Ok(address))
+}
+
+fn verify_hostname(cert: &X509Ref, hostname: &str) -> bool {
+    // Common name is only consulted if there are no DNS names in SAN.
+    let san_names: Vec<String> = cert
+        .subject_alt_names()
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(|name| name.dnsname().map(ToOwned::to_owned))
+                .collect()
+        })
+        .unwrap_or_default();
+    if !san_names.is_empty() {
+        return san_names
+            .iter()
+            .any(|pattern| hostname_matches(pattern, hostname));
+    }
+
+    common_names(cert).any(|name| hostname_matches(&name, hostname))
+}
+
+// Compares hostnames case-insensitively, allowing a wildcard in place
+// of the leftmost label of the pattern (e.g. `*.example.com`).
+fn hostname_matches(pattern: &str, hostname: &str) -> bool {
+    let pattern = pattern.trim_end_matches('.');
+    let hostname = hostname.trim_end_matches('.');
+
+    match pattern.strip_prefix("*.") {
+        Some(pattern_domain) => match hostname.split_once('.') {
+            Some((label, domain)) => {
+                !label.is_empty() && domain.eq_ignore_ascii_case(pattern_domain)
+            }
+            None => false,
+        },
+        None => pattern.eq_ignore_ascii_case(hostname),
+    }
 }
 
 #[no_mangle]
@@ -287,3 +1112,415 @@ pub unsafe extern "C" fn cass_ssl_set_private_key_n(
 
     CassError::CASS_OK
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::argconv::{make_c_str, ptr_to_ref};
+    use crate::session::{cass_session_free, cass_session_new, cass_session_reload_ssl};
+    use crate::testing::{assert_cass_error_eq, self_signed_cert, self_signed_cert_and_key};
+    use openssl::pkcs12::Pkcs12;
+    use openssl::pkey::PKey;
+    use openssl::stack::Stack;
+    use openssl::x509::X509;
+
+    #[test]
+    fn peer_identity_ip_address() {
+        let localhost: IpAddr = "127.0.0.1".parse().unwrap();
+        let other: IpAddr = "127.0.0.2".parse().unwrap();
+
+        // Common name only
+        let cert = self_signed_cert("127.0.0.1", &[], &[]).0;
+        assert!(verify_ip_address(&cert, localhost));
+        assert!(!verify_ip_address(&cert, other));
+
+        // SAN takes precedence over common name
+        let cert = self_signed_cert("127.0.0.1", &["127.0.0.2", "::1"], &[]).0;
+        assert!(!verify_ip_address(&cert, localhost));
+        assert!(verify_ip_address(&cert, other));
+        assert!(verify_ip_address(&cert, "::1".parse().unwrap()));
+
+        // Hostname is not an IP identity
+        let cert = self_signed_cert("localhost", &[], &["localhost"]).0;
+        assert!(!verify_ip_address(&cert, localhost));
+    }
+
+    #[test]
+    fn peer_identity_hostname() {
+        assert!(hostname_matches("node1.example.com", "NODE1.example.com."));
+        assert!(hostname_matches("*.example.com", "node1.example.com"));
+        assert!(!hostname_matches("*.example.com", "example.com"));
+        assert!(!hostname_matches("*.example.com", "a.node1.example.com"));
+        assert!(!hostname_matches("node1.example.com", "node2.example.com"));
+
+        // Common name only
+        let cert = self_signed_cert("node1.example.com", &[], &[]).0;
+        assert!(verify_hostname(&cert, "node1.example.com"));
+        assert!(!verify_hostname(&cert, "node2.example.com"));
+
+        // SAN takes precedence over common name
+        let cert = self_signed_cert("node1.example.com", &[], &["*.dc1.example.com"]).0;
+        assert!(!verify_hostname(&cert, "node1.example.com"));
+        assert!(verify_hostname(&cert, "node2.dc1.example.com"));
+    }
+
+    #[test]
+    fn tls_protocol_and_cipher_settings() {
+        unsafe {
+            let ssl = cass_ssl_new() as *mut CassSsl;
+
+            assert_cass_error_eq!(
+                cass_ssl_set_min_protocol_version(ssl, CASS_SSL_TLS_VERSION_1_2),
+                CassError::CASS_OK
+            );
+            assert_cass_error_eq!(
+                cass_ssl_set_max_protocol_version(ssl, CASS_SSL_TLS_VERSION_DEFAULT),
+                CassError::CASS_OK
+            );
+            assert_cass_error_eq!(
+                cass_ssl_set_min_protocol_version(ssl, 42),
+                CassError::CASS_ERROR_SSL_PROTOCOL_ERROR
+            );
+
+            assert_cass_error_eq!(
+                cass_ssl_set_cipher_list(
+                    ssl,
+                    make_c_str!("ECDHE-RSA-AES256-GCM-SHA384:ECDHE-RSA-AES128-GCM-SHA256")
+                ),
+                CassError::CASS_OK
+            );
+            assert_cass_error_eq!(
+                cass_ssl_set_cipher_list(ssl, make_c_str!("NOT-A-CIPHER")),
+                CassError::CASS_ERROR_SSL_PROTOCOL_ERROR
+            );
+            assert_cass_error_eq!(
+                cass_ssl_set_cipher_list(ssl, std::ptr::null()),
+                CassError::CASS_ERROR_SSL_PROTOCOL_ERROR
+            );
+
+            assert_cass_error_eq!(
+                cass_ssl_set_ciphersuites(ssl, make_c_str!("TLS_AES_256_GCM_SHA384")),
+                CassError::CASS_OK
+            );
+            assert_cass_error_eq!(
+                cass_ssl_set_ciphersuites(ssl, make_c_str!("NOT_A_CIPHERSUITE")),
+                CassError::CASS_ERROR_SSL_PROTOCOL_ERROR
+            );
+
+            assert_cass_error_eq!(
+                cass_ssl_set_groups(ssl, make_c_str!("P-256:P-384")),
+                CassError::CASS_OK
+            );
+            assert_cass_error_eq!(
+                cass_ssl_set_groups(ssl, make_c_str!("not-a-group")),
+                CassError::CASS_ERROR_SSL_PROTOCOL_ERROR
+            );
+
+            cass_ssl_free(ssl);
+        }
+    }
+
+    #[test]
+    fn reload_ssl_material() {
+        let (cert, key) = self_signed_cert_and_key();
+        let cert = CString::new(cert).unwrap();
+        let key = CString::new(key).unwrap();
+
+        unsafe {
+            let ssl_raw = cass_ssl_new() as *mut CassSsl;
+            let ssl = ptr_to_ref(ssl_raw);
+            let session_context = ssl.session_context();
+            let other_session_context = ssl.session_context();
+            let reload_state =
+                |ssl_context: &SslContext| ssl_context.ex_data(*RELOAD_STATE_INDEX).cloned();
+
+            // The reload callbacks are not installed until the first reload.
+            assert!(reload_state(&session_context).is_none());
+
+            assert_cass_error_eq!(
+                cass_ssl_add_trusted_cert(ssl_raw, cert.as_ptr()),
+                CassError::CASS_OK
+            );
+            reload_session_ssl(&session_context, ssl);
+            {
+                let state = reload_state(&session_context).unwrap();
+                let material = state.material.read().unwrap();
+                let material = material.as_ref().unwrap();
+                assert_eq!(
+                    OPENSSL_sk_num(material.trusted_certs as *const OPENSSL_STACK),
+                    1
+                );
+                assert!(material.cert.is_null());
+                assert!(material.key.is_null());
+            }
+
+            assert_cass_error_eq!(
+                cass_ssl_set_cert(ssl_raw, cert.as_ptr()),
+                CassError::CASS_OK
+            );
+            assert_cass_error_eq!(
+                cass_ssl_set_private_key(ssl_raw, key.as_ptr(), make_c_str!("") as *mut c_char),
+                CassError::CASS_OK
+            );
+            reload_session_ssl(&session_context, ssl);
+            {
+                let state = reload_state(&session_context).unwrap();
+                let material = state.material.read().unwrap();
+                let material = material.as_ref().unwrap();
+                assert!(!material.cert.is_null());
+                assert!(!material.key.is_null());
+                assert!(material.chain.is_empty());
+            }
+            // Other sessions are not affected.
+            assert!(reload_state(&other_session_context).is_none());
+
+            // Not connected session
+            let session_raw = cass_session_new();
+            assert_cass_error_eq!(
+                cass_session_reload_ssl(session_raw, ssl_raw),
+                CassError::CASS_ERROR_LIB_INVALID_STATE
+            );
+            cass_session_free(session_raw);
+
+            cass_ssl_free(ssl_raw);
+        }
+    }
+
+    #[test]
+    fn load_tls_material() {
+        let (cert1, key1) = self_signed_cert_and_key();
+        let (cert2, _) = self_signed_cert_and_key();
+        let dir = std::env::temp_dir().join(format!("cass_ssl_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let write_file = |name: &str, contents: &[u8]| {
+            let path = dir.join(name);
+            std::fs::write(&path, contents).unwrap();
+            CString::new(path.to_str().unwrap()).unwrap()
+        };
+        let trusted_count = |ssl: &CassSsl| unsafe {
+            OPENSSL_sk_num(X509_STORE_get0_objects(ssl.trusted_store) as *const OPENSSL_STACK)
+        };
+
+        unsafe {
+            {
+                // Multi-certificate PEM in memory and from a file
+                let ssl_raw = cass_ssl_new() as *mut CassSsl;
+                let chain = [&cert1[..], &cert2[..]].concat();
+                assert_cass_error_eq!(
+                    cass_ssl_add_trusted_cert_n(
+                        ssl_raw,
+                        chain.as_ptr() as *const c_char,
+                        chain.len() as size_t
+                    ),
+                    CassError::CASS_OK
+                );
+                assert_eq!(trusted_count(ptr_to_ref(ssl_raw)), 2);
+                cass_ssl_free(ssl_raw);
+
+                let ssl_raw = cass_ssl_new() as *mut CassSsl;
+                let path = write_file("chain.pem", &chain);
+                assert_cass_error_eq!(
+                    cass_ssl_add_trusted_cert_file(ssl_raw, path.as_ptr()),
+                    CassError::CASS_OK
+                );
+                assert_eq!(trusted_count(ptr_to_ref(ssl_raw)), 2);
+
+                let path = write_file("garbage.pem", b"garbage");
+                assert_cass_error_eq!(
+                    cass_ssl_add_trusted_cert_file(ssl_raw, path.as_ptr()),
+                    CassError::CASS_ERROR_SSL_INVALID_CERT
+                );
+                assert_cass_error_eq!(
+                    cass_ssl_add_trusted_cert_file(ssl_raw, make_c_str!("/nonexistent/ca.pem")),
+                    CassError::CASS_ERROR_SSL_INVALID_CERT
+                );
+                assert_cass_error_eq!(
+                    cass_ssl_add_system_trusted_certs(ssl_raw),
+                    CassError::CASS_OK
+                );
+                cass_ssl_free(ssl_raw);
+            }
+            {
+                // Certificate and key files
+                let ssl_raw = cass_ssl_new() as *mut CassSsl;
+                let cert_path = write_file("cert.pem", &cert1);
+                let key_path = write_file("key.pem", &key1);
+                assert_cass_error_eq!(
+                    cass_ssl_set_cert_file(ssl_raw, cert_path.as_ptr()),
+                    CassError::CASS_OK
+                );
+                assert_cass_error_eq!(
+                    cass_ssl_set_private_key_file(
+                        ssl_raw,
+                        key_path.as_ptr(),
+                        make_c_str!("") as *mut c_char
+                    ),
+                    CassError::CASS_OK
+                );
+                assert_cass_error_eq!(
+                    cass_ssl_set_cert_file(ssl_raw, make_c_str!("/nonexistent/cert.pem")),
+                    CassError::CASS_ERROR_SSL_INVALID_CERT
+                );
+                cass_ssl_free(ssl_raw);
+            }
+            {
+                // PKCS#12 bundle
+                let cert = X509::from_pem(&cert1).unwrap();
+                let key = PKey::private_key_from_pem(&key1).unwrap();
+                let p12 = Pkcs12::builder()
+                    .build("secret", "client", &key, &cert)
+                    .unwrap()
+                    .to_der()
+                    .unwrap();
+
+                let ssl_raw = cass_ssl_new() as *mut CassSsl;
+                assert_cass_error_eq!(
+                    cass_ssl_set_pkcs12(
+                        ssl_raw,
+                        p12.as_ptr(),
+                        p12.len() as size_t,
+                        make_c_str!("secret")
+                    ),
+                    CassError::CASS_OK
+                );
+                let ssl = ptr_to_ref(ssl_raw);
+                assert!(!SSL_CTX_get0_certificate(ssl.ssl_context).is_null());
+                assert!(!SSL_CTX_get0_privatekey(ssl.ssl_context).is_null());
+
+                assert_cass_error_eq!(
+                    cass_ssl_set_pkcs12(
+                        ssl_raw,
+                        p12.as_ptr(),
+                        p12.len() as size_t,
+                        make_c_str!("wrong")
+                    ),
+                    CassError::CASS_ERROR_SSL_INVALID_PRIVATE_KEY
+                );
+                assert_cass_error_eq!(
+                    cass_ssl_set_pkcs12(ssl_raw, b"garbage".as_ptr(), 7, make_c_str!("secret")),
+                    CassError::CASS_ERROR_SSL_INVALID_CERT
+                );
+
+                let path = write_file("client.p12", &p12);
+                assert_cass_error_eq!(
+                    cass_ssl_set_pkcs12_file(ssl_raw, path.as_ptr(), make_c_str!("secret")),
+                    CassError::CASS_OK
+                );
+                cass_ssl_free(ssl_raw);
+            }
+            {
+                // PKCS#12 bundle with a CA chain
+                let cert = X509::from_pem(&cert1).unwrap();
+                let key = PKey::private_key_from_pem(&key1).unwrap();
+                let mut ca = Stack::new().unwrap();
+                ca.push(X509::from_pem(&cert2).unwrap()).unwrap();
+                ca.push(self_signed_cert("ca", &[], &[]).0).unwrap();
+                let mut builder = Pkcs12::builder();
+                builder.ca(ca);
+                let p12 = builder
+                    .build("secret", "client", &key, &cert)
+                    .unwrap()
+                    .to_der()
+                    .unwrap();
+
+                let ssl_raw = cass_ssl_new() as *mut CassSsl;
+                assert_cass_error_eq!(
+                    cass_ssl_set_pkcs12(
+                        ssl_raw,
+                        p12.as_ptr(),
+                        p12.len() as size_t,
+                        make_c_str!("secret")
+                    ),
+                    CassError::CASS_OK
+                );
+                let ssl = ptr_to_ref(ssl_raw);
+                let mut chain: *mut stack_st_X509 = std::ptr::null_mut();
+                SSL_CTX_get_extra_chain_certs(ssl.ssl_context, &mut chain);
+                assert_eq!(OPENSSL_sk_num(chain as *const OPENSSL_STACK), 2);
+
+                // Setting the bundle again replaces the chain instead of extending it
+                assert_cass_error_eq!(
+                    cass_ssl_set_pkcs12(
+                        ssl_raw,
+                        p12.as_ptr(),
+                        p12.len() as size_t,
+                        make_c_str!("secret")
+                    ),
+                    CassError::CASS_OK
+                );
+                SSL_CTX_get_extra_chain_certs(ssl.ssl_context, &mut chain);
+                assert_eq!(OPENSSL_sk_num(chain as *const OPENSSL_STACK), 2);
+
+                // The chain certificates are kept by the context, and by the ones of the sessions.
+                let session_context = ssl.session_context();
+                SSL_CTX_get_extra_chain_certs(session_context.as_ptr(), &mut chain);
+                assert_eq!(OPENSSL_sk_num(chain as *const OPENSSL_STACK), 2);
+                reload_session_ssl(&session_context, ssl);
+                assert_eq!(
+                    session_context
+                        .ex_data(*RELOAD_STATE_INDEX)
+                        .unwrap()
+                        .material
+                        .read()
+                        .unwrap()
+                        .as_ref()
+                        .unwrap()
+                        .chain
+                        .len(),
+                    2
+                );
+                cass_ssl_free(ssl_raw);
+            }
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn peer_identity_verifier_flags() {
+        let verifier = PeerIdentityVerifier::default();
+        assert!(!verifier.is_enabled());
+
+        verifier
+            .flags
+            .store(CASS_SSL_VERIFY_PEER_CERT, Ordering::Relaxed);
+        assert!(!verifier.is_enabled());
+
+        verifier.flags.store(
+            CASS_SSL_VERIFY_PEER_CERT | CASS_SSL_VERIFY_PEER_IDENTITY,
+            Ordering::Relaxed,
+        );
+        assert!(verifier.is_enabled());
+
+        verifier.flags.store(
+            CASS_SSL_VERIFY_PEER_CERT | CASS_SSL_VERIFY_PEER_IDENTITY_DNS,
+            Ordering::Relaxed,
+        );
+        assert!(verifier.is_enabled());
+        assert!(verifier.verifies_hostname());
+    }
+
+    #[test]
+    fn peer_identity_verifier_checks_the_node() {
+        let localhost: IpAddr = "127.0.0.1".parse().unwrap();
+        let other: IpAddr = "127.0.0.2".parse().unwrap();
+        let cert = self_signed_cert("node1.example.com", &["127.0.0.1"], &["node1.example.com"]).0;
+
+        let verifier = PeerIdentityVerifier::new(CASS_SSL_VERIFY_PEER_IDENTITY);
+        assert!(verifier.verify_cert(&cert, localhost, None));
+        assert!(!verifier.verify_cert(&cert, other, None));
+
+        let verifier = PeerIdentityVerifier::new(CASS_SSL_VERIFY_PEER_IDENTITY_DNS);
+        assert!(verifier.verify_cert(&cert, other, Some("node1.example.com")));
+        assert!(!verifier.verify_cert(&cert, localhost, Some("node2.example.com")));
+        assert!(!verifier.verify_cert(&cert, localhost, None));
+
+        // Both identities have to match
+        let verifier = PeerIdentityVerifier::new(
+            CASS_SSL_VERIFY_PEER_IDENTITY | CASS_SSL_VERIFY_PEER_IDENTITY_DNS,
+        );
+        assert!(verifier.verify_cert(&cert, localhost, Some("node1.example.com")));
+        assert!(!verifier.verify_cert(&cert, other, Some("node1.example.com")));
+        assert!(!verifier.verify_cert(&cert, localhost, Some("node2.example.com")));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::argconv::*;
 use crate::cass_error::CassError;
 use crate::exec_profile::PerStatementExecProfile;
+use crate::inet::CassInet;
 use crate::query_result::CassResult;
 use crate::retry_policy::CassRetryPolicy;
 use crate::types::*;
@@ -14,7 +15,8 @@ use scylla::statement::prepared_statement::PreparedStatement;
 use scylla::statement::SerialConsistency;
 use scylla::{BufMut, Bytes, BytesMut};
 use std::collections::HashMap;
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
+use std::net::{IpAddr, SocketAddr};
 use std::os::raw::{c_char, c_int};
 use std::slice;
 use std::sync::Arc;
@@ -41,6 +43,8 @@ pub struct CassStatement {
     pub request_timeout_ms: Option<cass_uint64_t>,
 
     pub(crate) exec_profile: Option<PerStatementExecProfile>,
+    // Node the statement is sent to, bypassing the load balancing policy.
+    pub(crate) target_host: Option<SocketAddr>,
 }
 
 impl CassStatement {
@@ -163,6 +167,7 @@ pub unsafe extern "C" fn cass_statement_new_n(
         paging_state: None,
         request_timeout_ms: None,
         exec_profile: None,
+        target_host: None,
     }))
 }
 
@@ -276,6 +281,59 @@ pub unsafe extern "C" fn cass_statement_set_tracing(
     CassError::CASS_OK
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn cass_statement_set_host(
+    statement: *mut CassStatement,
+    host: *const c_char,
+    port: c_int,
+) -> CassError {
+    cass_statement_set_host_n(statement, host, strlen(host), port)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_statement_set_host_n(
+    statement: *mut CassStatement,
+    host: *const c_char,
+    host_length: size_t,
+    port: c_int,
+) -> CassError {
+    let address = match ptr_to_cstr_n(host, host_length).map(str::parse::<IpAddr>) {
+        Some(Ok(address)) => address,
+        _ => return CassError::CASS_ERROR_LIB_BAD_PARAMS,
+    };
+
+    set_target_host(statement, address, port)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_statement_set_host_inet(
+    statement: *mut CassStatement,
+    host: *const CassInet,
+    port: c_int,
+) -> CassError {
+    let address = match host.as_ref().map(|host| IpAddr::try_from(*host)) {
+        Some(Ok(address)) => address,
+        _ => return CassError::CASS_ERROR_LIB_BAD_PARAMS,
+    };
+
+    set_target_host(statement, address, port)
+}
+
+unsafe fn set_target_host(
+    statement: *mut CassStatement,
+    address: IpAddr,
+    port: c_int,
+) -> CassError {
+    let port = match u16::try_from(port) {
+        Ok(port) => port,
+        Err(_) => return CassError::CASS_ERROR_LIB_BAD_PARAMS,
+    };
+
+    ptr_to_ref_mut(statement).target_host = Some(SocketAddr::new(address, port));
+
+    CassError::CASS_OK
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn cass_statement_set_retry_policy(
     statement: *mut CassStatement,
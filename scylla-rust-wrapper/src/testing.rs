@@ -13,3 +13,62 @@ macro_rules! assert_cass_error_eq {
     }};
 }
 pub(crate) use assert_cass_error_eq;
+
+#[cfg(test)]
+use openssl::pkey::{PKey, Private};
+#[cfg(test)]
+use openssl::x509::X509;
+
+// Returns a self-signed certificate with the given subject alternative names, and its key.
+#[cfg(test)]
+pub(crate) fn self_signed_cert(
+    common_name: &str,
+    san_ips: &[&str],
+    san_dns: &[&str],
+) -> (X509, PKey<Private>) {
+    use openssl::asn1::Asn1Time;
+    use openssl::hash::MessageDigest;
+    use openssl::rsa::Rsa;
+    use openssl::x509::extension::SubjectAlternativeName;
+    use openssl::x509::X509NameBuilder;
+
+    let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+
+    let mut name = X509NameBuilder::new().unwrap();
+    name.append_entry_by_text("CN", common_name).unwrap();
+    let name = name.build();
+
+    let mut cert = X509::builder().unwrap();
+    cert.set_version(2).unwrap();
+    cert.set_subject_name(&name).unwrap();
+    cert.set_issuer_name(&name).unwrap();
+    cert.set_pubkey(&key).unwrap();
+    cert.set_not_before(&Asn1Time::days_from_now(0).unwrap())
+        .unwrap();
+    cert.set_not_after(&Asn1Time::days_from_now(1).unwrap())
+        .unwrap();
+    if !san_ips.is_empty() || !san_dns.is_empty() {
+        let mut san = SubjectAlternativeName::new();
+        san_ips.iter().for_each(|ip| {
+            san.ip(ip);
+        });
+        san_dns.iter().for_each(|dns| {
+            san.dns(dns);
+        });
+        let san = san.build(&cert.x509v3_context(None, None)).unwrap();
+        cert.append_extension(san).unwrap();
+    }
+    cert.sign(&key, MessageDigest::sha256()).unwrap();
+
+    (cert.build(), key)
+}
+
+// Returns a PEM-encoded self-signed certificate for 127.0.0.1 and its private key.
+#[cfg(test)]
+pub(crate) fn self_signed_cert_and_key() -> (Vec<u8>, Vec<u8>) {
+    let (cert, key) = self_signed_cert("127.0.0.1", &[], &[]);
+    (
+        cert.to_pem().unwrap(),
+        key.private_key_to_pem_pkcs8().unwrap(),
+    )
+}
@@ -0,0 +1,207 @@
+use crate::argconv::*;
+use crate::types::*;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+pub enum TimestampGenerator {
+    // Leaves assigning timestamps to the server.
+    ServerSide,
+    Monotonic(MonotonicTimestampGenerator),
+}
+
+pub type CassTimestampGen = TimestampGenerator;
+
+impl TimestampGenerator {
+    // Returns `None` if the timestamp should be assigned by the server.
+    pub(crate) fn next_timestamp(&self) -> Option<i64> {
+        match self {
+            TimestampGenerator::ServerSide => None,
+            TimestampGenerator::Monotonic(generator) => Some(generator.next()),
+        }
+    }
+}
+
+// Generates strictly increasing timestamps in microseconds, even across threads.
+// If the rate exceeds one timestamp per microsecond or the clock goes backwards,
+// the last timestamp is incremented until the clock catches up.
+pub struct MonotonicTimestampGenerator {
+    last: AtomicI64,
+    // Milliseconds since `started`, the monotonic clock keeps the warnings
+    // periodic regardless of the skew of the system clock.
+    last_warning: AtomicI64,
+    warning_threshold_us: i64,
+    warning_interval_ms: i64,
+    started: Instant,
+}
+
+impl MonotonicTimestampGenerator {
+    // Defaults of the CPP driver.
+    const DEFAULT_WARNING_THRESHOLD_US: i64 = 1_000_000;
+    const DEFAULT_WARNING_INTERVAL_MS: i64 = 1_000;
+
+    fn new(warning_threshold_us: i64, warning_interval_ms: i64) -> Self {
+        Self {
+            last: AtomicI64::new(0),
+            last_warning: AtomicI64::new(i64::MIN),
+            warning_threshold_us,
+            // Warnings can be logged at most once per millisecond.
+            warning_interval_ms: warning_interval_ms.max(1),
+            started: Instant::now(),
+        }
+    }
+
+    pub(crate) fn next(&self) -> i64 {
+        let mut last = self.last.load(Ordering::Relaxed);
+        loop {
+            let next = self.compute_next(last);
+            match self
+                .last
+                .compare_exchange_weak(last, next, Ordering::Relaxed, Ordering::Relaxed)
+            {
+                Ok(_) => return next,
+                Err(current) => last = current,
+            }
+        }
+    }
+
+    fn compute_next(&self, last: i64) -> i64 {
+        let current = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_micros() as i64);
+        if last < current {
+            return current;
+        }
+
+        // A negative threshold disables the warnings.
+        if self.warning_threshold_us >= 0 && last > current + self.warning_threshold_us {
+            let now = self.started.elapsed().as_millis() as i64;
+            let last_warning = self.last_warning.load(Ordering::Relaxed);
+            if now > last_warning.saturating_add(self.warning_interval_ms)
+                && self
+                    .last_warning
+                    .compare_exchange(last_warning, now, Ordering::Relaxed, Ordering::Relaxed)
+                    .is_ok()
+            {
+                tracing::warn!(
+                    "Clock skew detected. The current time ({}) was {} microseconds behind \
+                     the last generated timestamp ({}). The next generated timestamp will be \
+                     artificially incremented to guarantee monotonicity.",
+                    current,
+                    last - current,
+                    last
+                );
+            }
+        }
+        last + 1
+    }
+}
+
+impl Default for MonotonicTimestampGenerator {
+    fn default() -> Self {
+        Self::new(
+            Self::DEFAULT_WARNING_THRESHOLD_US,
+            Self::DEFAULT_WARNING_INTERVAL_MS,
+        )
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn cass_timestamp_gen_server_side_new() -> *const CassTimestampGen {
+    Arc::into_raw(Arc::new(TimestampGenerator::ServerSide))
+}
+
+#[no_mangle]
+pub extern "C" fn cass_timestamp_gen_monotonic_new() -> *const CassTimestampGen {
+    cass_timestamp_gen_monotonic_new_with_settings(
+        MonotonicTimestampGenerator::DEFAULT_WARNING_THRESHOLD_US,
+        MonotonicTimestampGenerator::DEFAULT_WARNING_INTERVAL_MS,
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn cass_timestamp_gen_monotonic_new_with_settings(
+    warning_threshold_us: cass_int64_t,
+    warning_interval_ms: cass_int64_t,
+) -> *const CassTimestampGen {
+    Arc::into_raw(Arc::new(TimestampGenerator::Monotonic(
+        MonotonicTimestampGenerator::new(warning_threshold_us, warning_interval_ms),
+    )))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cass_timestamp_gen_free(timestamp_gen: *const CassTimestampGen) {
+    free_arced(timestamp_gen);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn monotonic_timestamps() {
+        let generator = MonotonicTimestampGenerator::default();
+        let before = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_micros() as i64;
+
+        let mut last = generator.next();
+        assert!(last >= before);
+        for _ in 0..10000 {
+            let next = generator.next();
+            assert!(next > last);
+            last = next;
+        }
+    }
+
+    #[test]
+    fn monotonic_timestamps_across_threads() {
+        let generator = Arc::new(MonotonicTimestampGenerator::default());
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let generator = generator.clone();
+                std::thread::spawn(move || {
+                    let timestamps: Vec<i64> = (0..10000).map(|_| generator.next()).collect();
+                    assert!(timestamps.windows(2).all(|pair| pair[0] < pair[1]));
+                    timestamps
+                })
+            })
+            .collect();
+
+        // Timestamps are unique across threads
+        let mut all = HashSet::new();
+        for thread in threads {
+            for timestamp in thread.join().unwrap() {
+                assert!(all.insert(timestamp));
+            }
+        }
+    }
+
+    #[test]
+    fn clock_skew() {
+        let generator = MonotonicTimestampGenerator::new(0, 0);
+        // Timestamps far in the future, as if the clock went backwards
+        let future = generator.compute_next(0) + 60_000_000;
+        generator.last.store(future, Ordering::Relaxed);
+
+        assert_eq!(generator.next(), future + 1);
+        assert_eq!(generator.next(), future + 2);
+        // The skew exceeds the threshold, so it has been warned about
+        assert_ne!(generator.last_warning.load(Ordering::Relaxed), i64::MIN);
+    }
+
+    #[test]
+    fn server_side_timestamps() {
+        unsafe {
+            let server_side = cass_timestamp_gen_server_side_new();
+            assert_eq!(ptr_to_ref(server_side).next_timestamp(), None);
+            cass_timestamp_gen_free(server_side);
+
+            let monotonic = cass_timestamp_gen_monotonic_new_with_settings(-1, 0);
+            assert!(ptr_to_ref(monotonic).next_timestamp().is_some());
+            cass_timestamp_gen_free(monotonic);
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use crate::resolver::{reverse_resolve, ResolveConfig};
+use crate::ssl::PeerIdentityVerifier;
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use openssl::ssl::{Ssl, SslContext, SslVerifyMode};
+use scylla::transport::errors::TranslationError;
+use scylla::transport::session::{AddressTranslator, SessionConfig};
+use scylla::transport::topology::UntranslatedPeer;
+use socket2::{SockRef, TcpKeepalive};
+use std::collections::HashMap;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tokio_openssl::SslStream;
+
+// The Rust driver performs the TLS handshake itself and does not tell which node it is
+// performed with. Some handshakes need to know it though: the certificate of a node is
+// checked against the node's own address or hostname for CASS_SSL_VERIFY_PEER_IDENTITY[_DNS],
+// and the SNI proxy of a cloud cluster is sent the host ID of the node to route to.
+//
+// Such connections are made through tunnels instead: every node is given an endpoint on
+// the loopback interface, which the driver knows the node by and connects to in plain text,
+// and every connection to the endpoint is forwarded to the node over TLS.
+
+lazy_static! {
+    // Addresses of the nodes behind the endpoints of all the sessions.
+    static ref ENDPOINT_NODES: RwLock<HashMap<SocketAddr, SocketAddr>> = Default::default();
+}
+
+// Returns the address of the node the driver knows by `address`, which is
+// the address itself unless it is the endpoint of a tunnel.
+pub(crate) fn node_address(address: SocketAddr) -> SocketAddr {
+    ENDPOINT_NODES
+        .read()
+        .unwrap()
+        .get(&address)
+        .copied()
+        .unwrap_or(address)
+}
+
+// Where the connections to an endpoint are forwarded to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct TunnelTarget {
+    node: SocketAddr,
+    // Sent as SNI, e.g. the host ID of a node behind a cloud SNI proxy.
+    server_name: Option<String>,
+}
+
+struct Tunnel {
+    endpoint: SocketAddr,
+    accept_task: JoinHandle<()>,
+}
+
+impl Drop for Tunnel {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+        ENDPOINT_NODES.write().unwrap().remove(&self.endpoint);
+    }
+}
+
+// Settings of the TLS connections to the nodes.
+struct TunnelConfig {
+    ssl_context: SslContext,
+    peer_identity_verifier: Option<Arc<PeerIdentityVerifier>>,
+    resolve_config: ResolveConfig,
+    connect_timeout: Duration,
+    tcp_nodelay: bool,
+    tcp_keepalive_interval: Option<Duration>,
+}
+
+// Tunnels of a session, closed along with it.
+pub(crate) struct TlsTunnels {
+    config: Arc<TunnelConfig>,
+    tunnels: Mutex<HashMap<TunnelTarget, Tunnel>>,
+}
+
+impl TlsTunnels {
+    // The connections to the nodes follow the TCP settings of the session.
+    pub(crate) fn new(
+        ssl_context: SslContext,
+        peer_identity_verifier: Option<Arc<PeerIdentityVerifier>>,
+        resolve_config: ResolveConfig,
+        session_config: &SessionConfig,
+    ) -> Self {
+        Self {
+            config: Arc::new(TunnelConfig {
+                ssl_context,
+                peer_identity_verifier,
+                resolve_config,
+                connect_timeout: session_config.connect_timeout,
+                tcp_nodelay: session_config.tcp_nodelay,
+                tcp_keepalive_interval: session_config.tcp_keepalive_interval,
+            }),
+            tunnels: Default::default(),
+        }
+    }
+
+    // Returns the endpoint of the tunnel to `node`, opening the tunnel if there is none yet.
+    // Must be called from within the runtime.
+    pub(crate) fn endpoint(
+        &self,
+        node: SocketAddr,
+        server_name: Option<String>,
+    ) -> io::Result<SocketAddr> {
+        let target = TunnelTarget { node, server_name };
+        let mut tunnels = self.tunnels.lock().unwrap();
+        if let Some(tunnel) = tunnels.get(&target) {
+            return Ok(tunnel.endpoint);
+        }
+
+        let listener = std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        listener.set_nonblocking(true)?;
+        let listener = TcpListener::from_std(listener)?;
+        let endpoint = listener.local_addr()?;
+        ENDPOINT_NODES.write().unwrap().insert(endpoint, node);
+        let accept_task = tokio::spawn(accept(listener, self.config.clone(), target.clone()));
+        tunnels.insert(
+            target,
+            Tunnel {
+                endpoint,
+                accept_task,
+            },
+        );
+        Ok(endpoint)
+    }
+}
+
+async fn accept(listener: TcpListener, config: Arc<TunnelConfig>, target: TunnelTarget) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(forward(stream, config.clone(), target.clone()));
+            }
+            Err(err) => tracing::warn!(
+                "Unable to accept a connection to node {}: {}",
+                target.node,
+                err
+            ),
+        }
+    }
+}
+
+// Forwards a connection of the driver until either side closes it. The connection
+// is closed right away if the node cannot be connected to, e.g. because its identity
+// does not match its certificate, which the driver takes as a failure to connect.
+async fn forward(mut stream: TcpStream, config: Arc<TunnelConfig>, target: TunnelTarget) {
+    let mut node_stream = match config.connect(&target).await {
+        Ok(node_stream) => node_stream,
+        Err(err) => {
+            tracing::error!("Unable to connect to node {}: {}", target.node, err);
+            return;
+        }
+    };
+
+    if let Err(err) = tokio::io::copy_bidirectional(&mut stream, &mut node_stream).await {
+        tracing::debug!("Connection to node {} closed: {}", target.node, err);
+    }
+}
+
+impl TunnelConfig {
+    async fn connect(&self, target: &TunnelTarget) -> Result<SslStream<TcpStream>, String> {
+        let stream = tokio::time::timeout(self.connect_timeout, TcpStream::connect(target.node))
+            .await
+            .map_err(|_| "Connection timed out".to_string())?
+            .map_err(|err| err.to_string())?;
+        stream
+            .set_nodelay(self.tcp_nodelay)
+            .map_err(|err| err.to_string())?;
+        if let Some(interval) = self.tcp_keepalive_interval {
+            SockRef::from(&stream)
+                .set_tcp_keepalive(&TcpKeepalive::new().with_time(interval))
+                .map_err(|err| err.to_string())?;
+        }
+
+        let mut ssl = Ssl::new(&self.ssl_context).map_err(|err| err.to_string())?;
+        if let Some(server_name) = &target.server_name {
+            ssl.set_hostname(server_name)
+                .map_err(|err| err.to_string())?;
+        }
+        if let Some(verifier) = &self.peer_identity_verifier {
+            // The hostname of the node is resolved before the handshake,
+            // so that the certificate can be checked against it.
+            let hostname = if verifier.verifies_hostname() {
+                Some(reverse_resolve(target.node.ip(), &self.resolve_config).await?)
+            } else {
+                None
+            };
+            let verifier = verifier.clone();
+            let address = target.node.ip();
+            ssl.set_verify_callback(SslVerifyMode::PEER, move |preverify_ok, ctx| {
+                preverify_ok && verifier.verify(ctx, address, hostname.as_deref())
+            });
+        }
+
+        let mut stream = SslStream::new(ssl, stream).map_err(|err| err.to_string())?;
+        tokio::time::timeout(self.connect_timeout, Pin::new(&mut stream).connect())
+            .await
+            .map_err(|_| "TLS handshake timed out".to_string())?
+            .map_err(|err| format!("TLS handshake failed: {}", err))?;
+        Ok(stream)
+    }
+}
+
+// Gives every node discovered by the driver the endpoint of its tunnel. Nodes
+// behind a cloud SNI proxy all share its address, and are told apart by host ID.
+pub(crate) struct TunnelAddressTranslator {
+    inner: Option<Arc<dyn AddressTranslator>>,
+    tunnels: Arc<TlsTunnels>,
+    sni_proxy: Option<SocketAddr>,
+}
+
+impl TunnelAddressTranslator {
+    pub(crate) fn new(
+        inner: Option<Arc<dyn AddressTranslator>>,
+        tunnels: Arc<TlsTunnels>,
+        sni_proxy: Option<SocketAddr>,
+    ) -> Self {
+        Self {
+            inner,
+            tunnels,
+            sni_proxy,
+        }
+    }
+}
+
+#[async_trait]
+impl AddressTranslator for TunnelAddressTranslator {
+    async fn translate_address(
+        &self,
+        untranslated_peer: &UntranslatedPeer,
+    ) -> Result<SocketAddr, TranslationError> {
+        let (node, server_name) = match (self.sni_proxy, &self.inner) {
+            (Some(sni_proxy), _) => (sni_proxy, Some(untranslated_peer.host_id.to_string())),
+            (None, Some(inner)) => (inner.translate_address(untranslated_peer).await?, None),
+            (None, None) => (untranslated_peer.untranslated_address, None),
+        };
+
+        self.tunnels.endpoint(node, server_name).map_err(|err| {
+            tracing::error!("Unable to open a tunnel to node {}: {}", node, err);
+            TranslationError::NoRuleForAddress
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssl::{CASS_SSL_VERIFY_PEER_CERT, CASS_SSL_VERIFY_PEER_IDENTITY};
+    use crate::testing::self_signed_cert;
+    use openssl::ssl::{NameType, SslMethod};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // Echoes the server name sent by each client it accepts, then closes the connection.
+    async fn serve(cert_ip: &str) -> SocketAddr {
+        let (cert, key) = self_signed_cert(cert_ip, &[], &[]);
+        let mut builder = SslContext::builder(SslMethod::tls()).unwrap();
+        builder.set_certificate(&cert).unwrap();
+        builder.set_private_key(&key).unwrap();
+        let context = builder.build();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let mut stream = SslStream::new(Ssl::new(&context).unwrap(), stream).unwrap();
+                if Pin::new(&mut stream).accept().await.is_err() {
+                    continue;
+                }
+                let server_name = stream
+                    .ssl()
+                    .servername(NameType::HOST_NAME)
+                    .unwrap_or_default()
+                    .to_string();
+                let _ = stream.write_all(server_name.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+        address
+    }
+
+    fn tunnels(cert_ip: &str) -> TlsTunnels {
+        let (cert, _) = self_signed_cert(cert_ip, &[], &[]);
+        let mut builder = SslContext::builder(SslMethod::tls()).unwrap();
+        builder.cert_store_mut().add_cert(cert).unwrap();
+        builder.set_verify(SslVerifyMode::PEER);
+        let verifier =
+            PeerIdentityVerifier::new(CASS_SSL_VERIFY_PEER_CERT | CASS_SSL_VERIFY_PEER_IDENTITY);
+
+        TlsTunnels::new(
+            builder.build(),
+            Some(Arc::new(verifier)),
+            ResolveConfig::default(),
+            &SessionConfig::new(),
+        )
+    }
+
+    async fn read_through(endpoint: SocketAddr) -> String {
+        let mut stream = TcpStream::connect(endpoint).await.unwrap();
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response).await;
+        response
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(5000)]
+    async fn tunnels_verify_their_node() {
+        let node = serve("127.0.0.1").await;
+
+        // The certificate matches the address of the node.
+        let tunnels = tunnels("127.0.0.1");
+        let endpoint = tunnels.endpoint(node, Some("host1".to_string())).unwrap();
+        assert_eq!(node_address(endpoint), node);
+        assert_eq!(read_through(endpoint).await, "host1");
+
+        // Every node has its own endpoint.
+        assert_eq!(
+            tunnels.endpoint(node, Some("host1".to_string())).unwrap(),
+            endpoint
+        );
+        let other_endpoint = tunnels.endpoint(node, Some("host2".to_string())).unwrap();
+        assert_ne!(other_endpoint, endpoint);
+        assert_eq!(read_through(other_endpoint).await, "host2");
+
+        // Tunnels are closed along with the session.
+        drop(tunnels);
+        assert_eq!(node_address(endpoint), endpoint);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(TcpStream::connect(endpoint).await.is_err());
+
+        // The certificate of another node is rejected, even though it is trusted.
+        let tunnels = tunnels("127.0.0.2");
+        let endpoint = tunnels.endpoint(node, Some("host1".to_string())).unwrap();
+        assert_eq!(read_through(endpoint).await, "");
+    }
+}
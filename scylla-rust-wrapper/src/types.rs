@@ -18,6 +18,9 @@ pub type cass_byte_t = cass_uint8_t;
 pub type cass_duration_t = cass_uint64_t;
 pub type size_t = cass_uint64_t;
 
+// Releases the data of a callback once the driver no longer calls it.
+pub type CassDataCleanupCallback = Option<unsafe extern "C" fn(data: *mut ::std::os::raw::c_void)>;
+
 // Implementation directly ported from Cpp Driver implementation:
 
 const NUM_SECONDS_PER_DAY: i64 = 24 * 60 * 60;
@@ -47,59 +47,22 @@ CASS_EXPORT const CassDataType*
 cass_aggregate_meta_state_type(const CassAggregateMeta* aggregate_meta){
 	throw std::runtime_error("UNIMPLEMENTED cass_aggregate_meta_state_type\n");
 }
-CASS_EXPORT void
-cass_authenticator_set_error(CassAuthenticator* auth,
-                             const char* message){
-	throw std::runtime_error("UNIMPLEMENTED cass_authenticator_set_error\n");
+CASS_EXPORT CassError
+cass_batch_set_custom_payload(CassBatch* batch,
+                              const CassCustomPayload* payload){
+	throw std::runtime_error("UNIMPLEMENTED cass_batch_set_custom_payload\n");
 }
 CASS_EXPORT CassError
 cass_batch_set_keyspace(CassBatch* batch,
                         const char* keyspace){
 	throw std::runtime_error("UNIMPLEMENTED cass_batch_set_keyspace\n");
 }
-CASS_EXPORT CassError
-cass_cluster_set_authenticator_callbacks(CassCluster* cluster,
-                                         const CassAuthenticatorCallbacks* exchange_callbacks,
-                                         CassAuthenticatorDataCleanupCallback cleanup_callback,
-                                         void* data){
-	throw std::runtime_error("UNIMPLEMENTED cass_cluster_set_authenticator_callbacks\n");
-}
-CASS_EXPORT void
-cass_cluster_set_blacklist_dc_filtering(CassCluster* cluster,
-                                        const char* dcs){
-	throw std::runtime_error("UNIMPLEMENTED cass_cluster_set_blacklist_dc_filtering\n");
-}
-CASS_EXPORT void
-cass_cluster_set_blacklist_filtering(CassCluster* cluster,
-                                     const char* hosts){
-	throw std::runtime_error("UNIMPLEMENTED cass_cluster_set_blacklist_filtering\n");
-}
-CASS_EXPORT CassError
-cass_cluster_set_cloud_secure_connection_bundle_no_ssl_lib_init(CassCluster* cluster,
-                                                                const char* path){
-	throw std::runtime_error("UNIMPLEMENTED cass_cluster_set_cloud_secure_connection_bundle_no_ssl_lib_init\n");
-}
-CASS_EXPORT void
-cass_cluster_set_connection_heartbeat_interval(CassCluster* cluster,
-                                               unsigned interval_secs){
-	throw std::runtime_error("UNIMPLEMENTED cass_cluster_set_connection_heartbeat_interval\n");
-}
-CASS_EXPORT void
-cass_cluster_set_connection_idle_timeout(CassCluster* cluster,
-                                         unsigned timeout_secs){
-	throw std::runtime_error("UNIMPLEMENTED cass_cluster_set_connection_idle_timeout\n");
-}
 CASS_EXPORT void
 cass_cluster_set_constant_reconnect(CassCluster* cluster,
                                     cass_uint64_t delay_ms){
 	throw std::runtime_error("UNIMPLEMENTED cass_cluster_set_constant_reconnect\n");
 }
 CASS_EXPORT CassError
-cass_cluster_set_core_connections_per_host(CassCluster* cluster,
-                                           unsigned num_connections){
-	throw std::runtime_error("UNIMPLEMENTED cass_cluster_set_core_connections_per_host\n");
-}
-CASS_EXPORT CassError
 cass_cluster_set_host_listener_callback(CassCluster* cluster,
                                         CassHostListenerCallback callback,
                                         void* data){
@@ -111,6 +74,18 @@ cass_cluster_set_local_address(CassCluster* cluster,
 	throw std::runtime_error("UNIMPLEMENTED cass_cluster_set_local_address\n");
 }
 CASS_EXPORT CassError
+cass_cluster_set_local_address_n(CassCluster* cluster,
+                                 const char* name,
+                                 size_t name_length){
+	throw std::runtime_error("UNIMPLEMENTED cass_cluster_set_local_address_n\n");
+}
+CASS_EXPORT CassError
+cass_cluster_set_local_port_range(CassCluster* cluster,
+                                  int lo,
+                                  int hi){
+	throw std::runtime_error("UNIMPLEMENTED cass_cluster_set_local_port_range\n");
+}
+CASS_EXPORT CassError
 cass_cluster_set_no_compact(CassCluster* cluster,
                             cass_bool_t enabled){
 	throw std::runtime_error("UNIMPLEMENTED cass_cluster_set_no_compact\n");
@@ -140,21 +115,6 @@ cass_cluster_set_retry_policy(CassCluster* cluster,
                               CassRetryPolicy* retry_policy){
 	throw std::runtime_error("UNIMPLEMENTED cass_cluster_set_retry_policy\n");
 }
-CASS_EXPORT void
-cass_cluster_set_timestamp_gen(CassCluster* cluster,
-                               CassTimestampGen* timestamp_gen){
-	throw std::runtime_error("UNIMPLEMENTED cass_cluster_set_timestamp_gen\n");
-}
-CASS_EXPORT void
-cass_cluster_set_whitelist_dc_filtering(CassCluster* cluster,
-                                        const char* dcs){
-	throw std::runtime_error("UNIMPLEMENTED cass_cluster_set_whitelist_dc_filtering\n");
-}
-CASS_EXPORT void
-cass_cluster_set_whitelist_filtering(CassCluster* cluster,
-                                     const char* hosts){
-	throw std::runtime_error("UNIMPLEMENTED cass_cluster_set_whitelist_filtering\n");
-}
 CASS_EXPORT CassError
 cass_collection_append_custom(CassCollection* collection,
                               const char* class_name,
@@ -192,25 +152,13 @@ cass_custom_payload_set(CassCustomPayload* payload,
                         size_t value_size){
 	throw std::runtime_error("UNIMPLEMENTED cass_custom_payload_set\n");
 }
-CASS_EXPORT CassError
-cass_execution_profile_set_blacklist_dc_filtering(CassExecProfile* profile,
-                                                  const char* dcs){
-	throw std::runtime_error("UNIMPLEMENTED cass_execution_profile_set_blacklist_dc_filtering\n");
-}
-CASS_EXPORT CassError
-cass_execution_profile_set_blacklist_filtering(CassExecProfile* profile,
-                                               const char* hosts){
-	throw std::runtime_error("UNIMPLEMENTED cass_execution_profile_set_blacklist_filtering\n");
-}
-CASS_EXPORT CassError
-cass_execution_profile_set_whitelist_dc_filtering(CassExecProfile* profile,
-                                                  const char* dcs){
-	throw std::runtime_error("UNIMPLEMENTED cass_execution_profile_set_whitelist_dc_filtering\n");
-}
-CASS_EXPORT CassError
-cass_execution_profile_set_whitelist_filtering(CassExecProfile* profile,
-                                               const char* hosts){
-	throw std::runtime_error("UNIMPLEMENTED cass_execution_profile_set_whitelist_filtering\n");
+CASS_EXPORT void
+cass_custom_payload_set_n(CassCustomPayload* payload,
+                          const char* name,
+                          size_t name_length,
+                          const cass_byte_t* value,
+                          size_t value_size){
+	throw std::runtime_error("UNIMPLEMENTED cass_custom_payload_set_n\n");
 }
 CASS_EXPORT CassError
 cass_function_meta_argument(const CassFunctionMeta* function_meta,
@@ -394,18 +342,6 @@ cass_statement_set_custom_payload(CassStatement* statement,
 	throw std::runtime_error("UNIMPLEMENTED cass_statement_set_custom_payload\n");
 }
 CASS_EXPORT CassError
-cass_statement_set_host(CassStatement* statement,
-                        const char* host,
-                        int port){
-	throw std::runtime_error("UNIMPLEMENTED cass_statement_set_host\n");
-}
-CASS_EXPORT CassError
-cass_statement_set_host_inet(CassStatement* statement,
-                             const CassInet* host,
-                             int port){
-	throw std::runtime_error("UNIMPLEMENTED cass_statement_set_host_inet\n");
-}
-CASS_EXPORT CassError
 cass_statement_set_keyspace(CassStatement* statement,
                             const char* keyspace){
 	throw std::runtime_error("UNIMPLEMENTED cass_statement_set_keyspace\n");